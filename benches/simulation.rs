@@ -0,0 +1,76 @@
+//! How fast journeys are drawn: one click at a time, and in bulk for scenarios.
+//!
+//! Run with `cargo bench --bench simulation`. Bulk simulation should manage at least
+//! [TARGET] transitions a second on one core, which is checked once the benchmarks are done.
+
+use std::time::Instant;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use vocar::{model::Model, Class, Demo, Era, Race};
+
+fn single_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single_step");
+    group.throughput(Throughput::Elements(1));
+
+    let mut demo = Demo::with_seed(1);
+    demo.draw_next();
+    demo.draw_next();
+    group.bench_function("demo_next_gen", |b| {
+        b.iter_batched_ref(|| demo.clone(), |demo| demo.next_gen(), BatchSize::SmallInput);
+    });
+
+    let rules = Model::baseline().rules(Race::Black).clone();
+    let sampler = rules.sampler();
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    group.bench_function("rules_next", |b| {
+        b.iter(|| rules.next(Era::Tens, black_box(Class::Quintile(3)), 3, rng.gen()));
+    });
+    group.bench_function("sampler_next", |b| {
+        b.iter(|| sampler.next(Era::Tens, black_box(Class::Quintile(3)), 3, rng.gen()));
+    });
+
+    group.finish();
+}
+
+/// Transitions a second bulk simulation must manage on one core.
+const TARGET: f64 = 10_000_000.0;
+
+const RUNS: usize = 100_000;
+
+fn bulk(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk");
+    group.throughput(Throughput::Elements((RUNS * Demo::GENERATIONS) as u64));
+
+    let model = Model::baseline();
+    group.bench_function("simulate", |b| {
+        b.iter(|| model.simulate(Race::Black, Demo::GENERATIONS, RUNS, black_box(1)));
+    });
+    group.bench_function("simulate_paired", |b| {
+        b.iter(|| model.simulate_paired(Race::Black, Demo::GENERATIONS, RUNS, black_box(1)));
+    });
+
+    group.finish();
+}
+
+/// Report how many transitions a second bulk simulation manages, and fail below [TARGET]. Only
+/// optimized builds are held to it, not `cargo test --benches`.
+fn target(_: &mut Criterion) {
+    let model = Model::baseline();
+    for (name, paired) in [("simulate", false), ("simulate_paired", true)] {
+        let start = Instant::now();
+        if paired {
+            black_box(model.simulate_paired(Race::Black, Demo::GENERATIONS, 10 * RUNS, black_box(2)));
+        } else {
+            black_box(model.simulate(Race::Black, Demo::GENERATIONS, 10 * RUNS, black_box(2)));
+        }
+        let rate = (10 * RUNS * Demo::GENERATIONS) as f64 / start.elapsed().as_secs_f64();
+
+        println!("bulk/{name}: {:.1} million transitions a second, target {:.0} million", rate / 1e6, TARGET / 1e6);
+        assert!(cfg!(debug_assertions) || rate >= TARGET, "bulk/{name} is below the target");
+    }
+}
+
+criterion_group!(benches, single_step, bulk, target);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+use egui::accesskit::{Live, Role};
+
+/// A heading that screen readers announce as one.
+/// Results are `live`, so they're read out as soon as the screen shows them.
+pub fn heading(ui: &mut egui::Ui, text: impl Into<egui::RichText>, live: bool) -> egui::Response {
+    let response = ui.heading(text);
+    ui.ctx().accesskit_node_builder(response.id, |node| {
+        node.set_role(Role::Heading);
+        node.set_hierarchical_level(1);
+        if live {
+            node.set_live(Live::Polite);
+        }
+    });
+    response
+}
+
+/// The main button of a screen. It takes keyboard focus when nothing else has it,
+/// so Enter or Space moves on without reaching for the mouse.
+pub fn primary_button(ui: &mut egui::Ui, text: &str, hover: &str) -> egui::Response {
+    let response = ui.button(text).on_hover_text(hover);
+    if ui.memory(|memory| memory.focus().is_none()) {
+        response.request_focus();
+    }
+    response
+}
+
+/// A read-only `label` / `value` row, read out as a single line of text.
+pub fn field(ui: &mut egui::Ui, label: &str, value: &str) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let response = ui.strong(value);
+        set_name(ui, &response, &format!("{label}{value}"));
+    });
+}
+
+/// Give `response` the name a screen reader reads, e.g. a text alternative for a chart.
+pub fn set_name(ui: &egui::Ui, response: &egui::Response, name: &str) {
+    ui.ctx().accesskit_node_builder(response.id, |node| node.set_name(name));
+}
+
+/// Black and white visuals with bold outlines, for participants with low vision.
+#[must_use]
+pub fn high_contrast_visuals() -> egui::Visuals {
+    use egui::{Color32, Stroke};
+
+    let mut visuals = egui::Visuals::dark();
+    visuals.override_text_color = Some(Color32::WHITE);
+    visuals.panel_fill = Color32::BLACK;
+    visuals.window_fill = Color32::BLACK;
+    visuals.extreme_bg_color = Color32::BLACK;
+    visuals.faint_bg_color = Color32::from_gray(24);
+    visuals.hyperlink_color = Color32::YELLOW;
+    visuals.selection.bg_fill = Color32::from_rgb(0, 70, 160);
+    visuals.selection.stroke = Stroke::new(2.0, Color32::YELLOW);
+
+    for widget in [
+        &mut visuals.widgets.noninteractive,
+        &mut visuals.widgets.inactive,
+        &mut visuals.widgets.hovered,
+        &mut visuals.widgets.active,
+        &mut visuals.widgets.open,
+    ] {
+        widget.bg_fill = Color32::BLACK;
+        widget.weak_bg_fill = Color32::BLACK;
+        widget.bg_stroke = Stroke::new(2.0, Color32::WHITE);
+        widget.fg_stroke = Stroke::new(2.0, Color32::WHITE);
+    }
+    visuals.widgets.hovered.bg_stroke = Stroke::new(3.0, Color32::YELLOW);
+    visuals.widgets.active.bg_stroke = Stroke::new(3.0, Color32::YELLOW);
+
+    visuals
+}
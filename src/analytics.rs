@@ -0,0 +1,233 @@
+//! An anonymous log of how far journeys get and how long each [Screen] takes, for evaluating the
+//! activity.
+//!
+//! Nothing is recorded until it is switched on. The log stays on the device, in the app's storage,
+//! until it is exported: no names or free text are kept, and nothing is ever sent anywhere.
+
+use crate::{
+    app::Screen,
+    demographic::{Class, Demo, Race},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds since the Unix epoch.
+#[cfg(not(target_arch = "wasm32"))]
+#[must_use]
+pub fn now() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |since| since.as_secs_f64())
+}
+
+/// Seconds since the Unix epoch.
+#[cfg(target_arch = "wasm32")]
+#[must_use]
+pub fn now() -> f64 {
+    js_sys::Date::now() / 1000.0
+}
+
+/// Time spent on one [Screen]. Times are in seconds since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Visit {
+    pub screen: Screen,
+    pub entered: f64,
+    /// `None` while still on the [Screen], or if the app was closed on it.
+    pub left: Option<f64>,
+}
+
+/// What one draw of the journey came out as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Outcome {
+    Race(Race),
+    StartingClass(Class),
+    /// The [Class] of a generation, counting the first one after the starting [Class] as 1.
+    Generation { generation: usize, class: Class },
+}
+
+/// A draw and when it was made.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Step {
+    pub at: f64,
+    pub outcome: Outcome,
+}
+
+/// How a [Session] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Status {
+    /// Still going.
+    InProgress,
+    /// Reached [Screen::End].
+    Finished,
+    /// Went back to [Screen::Start], closed the app or stopped recording before the end.
+    Abandoned,
+}
+
+/// Everything from leaving [Screen::Start] up to going back to it. Sessions that end without a
+/// single draw, like a launch left on [Screen::Start] or a look at the scenarios, aren't kept.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Session {
+    pub started: f64,
+    /// The journey's [Race], once drawn.
+    pub race: Option<Race>,
+    pub visits: Vec<Visit>,
+    pub steps: Vec<Step>,
+    pub status: Status,
+}
+
+impl Session {
+    /// Seconds from leaving [Screen::Start] to the last [Visit] left.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        let last = self.visits.iter().filter_map(|visit| visit.left).fold(self.started, f64::max);
+        last - self.started
+    }
+
+    /// End the [Session], leaving its last [Screen] at `left` if that is known.
+    fn end(&mut self, left: Option<f64>) {
+        if let (Some(visit), Some(left)) = (self.visits.last_mut(), left) {
+            visit.left.get_or_insert(left);
+        }
+        let finished = self.visits.iter().any(|visit| visit.screen == Screen::End);
+        self.status = if finished { Status::Finished } else { Status::Abandoned };
+    }
+}
+
+/// The log, and whether it is being kept. Remembered between runs.
+///
+/// # Example
+/// ```
+/// use vocar::{analytics::{Analytics, Status}, Demo, Screen};
+///
+/// let mut analytics = Analytics::default();
+/// analytics.enabled = true;
+/// let mut demo = Demo::with_seed(1);
+///
+/// analytics.observe(0.0, Screen::Start, &demo);
+/// analytics.observe(1.0, Screen::RaceDetermination, &demo);
+/// demo.draw_next();
+/// analytics.observe(2.0, Screen::RaceDisplay, &demo);
+/// analytics.observe(5.0, Screen::Start, &demo);
+///
+/// assert_eq!(analytics.sessions.len(), 1);
+/// assert_eq!(analytics.sessions[0].status, Status::Abandoned);
+/// assert_eq!(analytics.sessions[0].duration(), 4.0);
+/// assert_eq!(analytics.sessions[0].steps.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Analytics {
+    /// Nothing is recorded unless this is set.
+    pub enabled: bool,
+    /// Every [Session] recorded, oldest first.
+    pub sessions: Vec<Session>,
+    /// How many draws of the journey have been recorded already.
+    seen: usize,
+}
+
+impl Analytics {
+    /// Storage key the [Analytics] are saved under, apart from the rest of the app's state.
+    pub const KEY: &'static str = "vocar_analytics";
+
+    fn current(&mut self) -> Option<&mut Session> {
+        self.sessions.last_mut().filter(|session| session.status == Status::InProgress)
+    }
+
+    /// Note that `screen` is showing `demo` at `now`. Called every frame; only changes are kept.
+    pub fn observe(&mut self, now: f64, screen: Screen, demo: &Demo) {
+        if !self.enabled {
+            self.interrupt(now);
+            return;
+        }
+
+        let on = self.current().and_then(|session| session.visits.last()).map(|visit| visit.screen);
+        if on == Some(screen) {
+            self.record_steps(now, demo);
+            return;
+        }
+
+        if screen == Screen::Start {
+            self.end(Some(now));
+            self.record_steps(now, demo);
+            return;
+        }
+
+        if let Some(visit) = self.current().and_then(|session| session.visits.last_mut()) {
+            visit.left = Some(now);
+        }
+        if self.current().is_none() {
+            self.sessions.push(Session {
+                started: now,
+                race: None,
+                visits: Vec::new(),
+                steps: Vec::new(),
+                status: Status::InProgress,
+            });
+            // Draws made before the session began, e.g. before recording was switched on, aren't its own.
+            self.seen = demo.draws();
+        }
+
+        if let Some(session) = self.current() {
+            session.visits.push(Visit { screen, entered: now, left: None });
+        }
+        self.record_steps(now, demo);
+    }
+
+    /// Add the draws of `demo` made since last time to the current [Session].
+    fn record_steps(&mut self, now: f64, demo: &Demo) {
+        let draws = demo.draws();
+        let seen = std::mem::replace(&mut self.seen, draws);
+        let Some(session) = self.current() else {
+            return;
+        };
+
+        session.race = demo.race.or(session.race);
+        // Fewer draws than before means some were undone; they were recorded when made.
+        for draw in seen..draws {
+            let outcome = match draw {
+                0 => demo.race.map(Outcome::Race),
+                1 => demo.class_zero.map(Outcome::StartingClass),
+                n => demo.history.get(n - 2).map(|&class| Outcome::Generation { generation: n - 1, class }),
+            };
+            if let Some(outcome) = outcome {
+                session.steps.push(Step { at: now, outcome });
+            }
+        }
+    }
+
+    /// End the current [Session], if there is one, leaving its last [Screen] at `left` if that
+    /// is known. Without any draws it is dropped.
+    fn end(&mut self, left: Option<f64>) {
+        let Some(session) = self.current() else {
+            return;
+        };
+        session.end(left);
+        if session.steps.is_empty() {
+            self.sessions.pop();
+        }
+    }
+
+    /// End the current [Session], if there is one, because recording stopped.
+    pub fn interrupt(&mut self, now: f64) {
+        self.end(Some(now));
+    }
+
+    /// End the [Session] the app was last closed in, if there is one. When its last [Screen] was
+    /// left isn't known.
+    pub fn reopen(&mut self) {
+        self.end(None);
+    }
+
+    /// Forget every [Session].
+    pub fn clear(&mut self) {
+        self.sessions.clear();
+    }
+
+    /// Every [Session] as pretty-printed JSON, for the people evaluating the activity.
+    ///
+    /// # Errors
+    /// Fails only if a time isn't a finite number.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.sessions)
+    }
+}
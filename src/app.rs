@@ -1,9 +1,30 @@
-use crate::demographic;
+use std::sync::Arc;
+
+use crate::{
+    a11y,
+    analytics::{self, Analytics},
+    card,
+    classroom::Roster,
+    demographic,
+    download,
+    flow::{self, Action, State},
+    i18n::{Lang, Localize},
+    layout::{self, View},
+    lineage::{Lineage, Person},
+    model::{self, Intervention, Method},
+    nav::Navigator,
+    quiz::{Guess, Prediction, Quiz},
+    share::{self, CodeError},
+    stats::{self, Group},
+    story,
+    survey::{self, Answer, Answers, Item, Kind, Survey, Surveys},
+    wealth::Table
+};
 
 //use rand::random;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Screen {
     #[default]
     Start,
@@ -13,49 +34,272 @@ pub enum Screen {
     ClassDisplay,
     FactDisplay,
     MissedFactDisplay,
-    End
+    End,
+    RosterSetup,
+    RosterDraw,
+    Scoreboard,
+    SharedJourney,
+    Scenarios,
+    Lineage,
+    PreSurvey,
+    PostSurvey
+}
+
+impl Screen {
+    /// Whether leaving this [Screen] draws something random for the [demographic::Demo].
+    #[must_use]
+    pub fn rolls_dice(&self) -> bool {
+        matches!(self, Screen::RaceDetermination | Screen::ClassDetermination | Screen::RosterDraw)
+    }
+
+    /// Whether arriving on this [Screen] forgets the way back, because earlier screens would redo its draws.
+    #[must_use]
+    pub fn starts_over(&self) -> bool {
+        matches!(self, Screen::Start | Screen::RosterDraw)
+    }
 }
 
 #[derive(Default, serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct Vocar {
     dem: demographic::Demo,
-    scr: Screen
+    scr: Screen,
+    nav: Navigator,
+    lang: Lang,
+    view: View,
+    roster: Roster,
+    /// Interventions being tried out on [Screen::Scenarios].
+    interventions: Vec<Intervention>,
+    /// How family trees are grown on [Screen::Lineage].
+    lineage: Lineage,
+    /// Guesses made before each draw in prediction mode.
+    quiz: Quiz,
+    /// Saved under its own key, so it outlives the rest of the state.
+    #[serde(skip)]
+    analytics: Analytics,
+    /// Saved under its own key, like the analytics.
+    #[serde(skip)]
+    surveys: Surveys,
+    /// A journey opened from its code, shown read-only.
+    #[serde(skip)]
+    shared: Option<demographic::Demo>,
+    #[serde(skip)]
+    share_error: Option<CodeError>,
+    /// Where the web build is served from, for links to journeys.
+    #[serde(skip)]
+    home: Option<String>
 }
 
 impl Vocar { 
     #[must_use]
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         /*
         if let Some(storage) = cc.storage {
             return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default(); 
         }
         */
-        Vocar::default() 
+        let view = cc.storage
+            .and_then(|storage| eframe::get_value(storage, View::KEY))
+            .unwrap_or_default();
+
+        let mut analytics: Analytics = cc.storage
+            .and_then(|storage| eframe::get_value(storage, Analytics::KEY))
+            .unwrap_or_default();
+        analytics.reopen();
+        let surveys = cc.storage
+            .and_then(|storage| eframe::get_value(storage, Surveys::KEY))
+            .unwrap_or_default();
+
+        let vocar = Vocar { view, analytics, surveys, ..Vocar::default() };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = survey::beside_app() {
+            match Survey::from_path(&path) {
+                Ok(loaded) => vocar.surveys.ask(loaded),
+                Err(error) => tracing::warn!(path = %path.display(), %error, "asking the built-in survey"),
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        let vocar = {
+            let mut vocar = vocar;
+            let location = &cc.integration_info.web_info.location;
+            vocar.home = location.url.split('?').next().map(str::to_string);
+            if let Some(code) = location.query_map.get(share::QUERY) {
+                vocar.open_code(code);
+            }
+            if let Some(url) = location.query_map.get(survey::QUERY).cloned() {
+                let (asked, ctx) = (vocar.surveys.asked(), cc.egui_ctx.clone());
+                wasm_bindgen_futures::spawn_local(async move {
+                    match Survey::fetch(&url).await {
+                        Ok(loaded) => {
+                            let _ = asked.set(loaded);
+                            ctx.request_repaint();
+                        }
+                        Err(error) => tracing::warn!(%url, %error, "asking the built-in survey"),
+                    }
+                });
+            }
+            vocar
+        };
+
+        vocar
+    }
+
+    /// The [Screen] being shown.
+    #[must_use]
+    pub fn screen(&self) -> Screen {
+        self.scr
+    }
+
+    /// The single participant's journey.
+    #[must_use]
+    pub fn demo(&self) -> &demographic::Demo {
+        &self.dem
+    }
+
+    /// Let going back undo draws, for a facilitator running the activity. Set from a launch option
+    /// rather than in the app, so participants can't redraw an outcome they don't like.
+    pub fn set_facilitator(&mut self, facilitator: bool) {
+        self.nav.facilitator = facilitator;
+    }
+
+    /// Show the journey `code` was made from, instead of starting a new one.
+    pub fn open_code(&mut self, code: &str) {
+        match share::decode(code) {
+            Ok(journey) => {
+                self.shared = Some(journey);
+                self.scr = Screen::SharedJourney;
+            }
+            Err(error) => self.share_error = Some(error),
+        }
+    }
+
+    /// Draw one frame of the current [Screen] and act on what was pressed. Needs no window, so the
+    /// app can also be driven from a bare [egui::Context].
+    pub fn show(&mut self, ctx: &egui::Context, native_pixels_per_point: Option<f32>) {
+        let Self { dem, scr, nav, lang, view, roster, interventions, lineage, quiz, analytics, surveys, shared, share_error, home } = self;
+
+        view.apply(ctx, native_pixels_per_point);
+        nav_bar(ctx, nav, lang, view, dem, scr);
+        let lang = *lang;
+
+        *scr = flow::guard(*scr, State { demo: dem, roster, shared: shared.as_ref(), surveys: surveys.enabled });
+        let (previous_screen, previous_demo) = (*scr, dem.clone());
+
+        let action = match *scr {
+            Screen::Start => start_screen(ctx, lang, share_error.is_some(), quiz, analytics, surveys),
+            Screen::RaceDetermination => race_determination_screen(ctx, lang, dem),
+            Screen::RaceDisplay => race_display_screen(ctx, lang, dem),
+            Screen::ClassDetermination => class_determination_screen(ctx, lang, dem, quiz),
+            Screen::ClassDisplay => class_display_screen(ctx, lang, dem, quiz),
+            Screen::FactDisplay => fact_screen(ctx, lang, dem),
+            Screen::MissedFactDisplay => missed_fact_screen(ctx, lang, dem),
+            Screen::End => end_screen(ctx, lang, dem, home.as_deref(), surveys.enabled, quiz),
+            Screen::RosterSetup => roster_setup_screen(ctx, lang, roster),
+            Screen::RosterDraw => roster_draw_screen(ctx, lang, roster),
+            Screen::Scoreboard => scoreboard_screen(ctx, lang, roster),
+            Screen::SharedJourney => shared.as_ref().and_then(|journey| shared_journey_screen(ctx, lang, journey)),
+            Screen::Scenarios => scenarios_screen(ctx, lang, interventions),
+            Screen::Lineage => lineage_screen(ctx, lang, lineage),
+            Screen::PreSurvey => pre_survey_screen(ctx, lang, surveys),
+            Screen::PostSurvey => post_survey_screen(ctx, lang, surveys, dem)
+        };
+
+        if let Some(action) = action {
+            let state = State { demo: dem, roster, shared: shared.as_ref(), surveys: surveys.enabled };
+            match flow::transition(*scr, state, action) {
+                Ok(next) => {
+                    tracing::debug!(from = ?*scr, to = ?next, ?action, "changed screen");
+                    // A journey begun from the start never carries on from the one before it, however
+                    // that one was left, e.g. by opening the scenarios from the end.
+                    let begins = *scr == Screen::Start && matches!(next, Screen::PreSurvey | Screen::RaceDetermination);
+                    if begins && dem.draws() > 0 {
+                        *dem = demographic::Demo::new();
+                    }
+                    *scr = next;
+                }
+                // Stay put and undo whatever the screen did rather than draw a screen that can't be drawn.
+                Err(error) => {
+                    tracing::warn!(screen = ?*scr, ?action, %error, "stayed on the screen");
+                    *dem = previous_demo.clone();
+                }
+            }
+        }
+
+        if *scr != Screen::Start {
+            *share_error = None;
+        }
+
+        if scr.starts_over() {
+            nav.clear();
+        } else if *scr != previous_screen {
+            nav.push(previous_screen, &previous_demo);
+        }
+
+        analytics.observe(analytics::now(), *scr, dem);
     }
 }
 
 impl eframe::App for Vocar {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, eframe::APP_KEY, self);
+        eframe::set_value(storage, View::KEY, &self.view);
+        eframe::set_value(storage, Analytics::KEY, &self.analytics);
+        eframe::set_value(storage, Surveys::KEY, &self.surveys);
     }
 
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let Self { dem, scr } = self;
-
-        match scr {
-            Screen::Start => start_screen(ctx, scr),
-            Screen::RaceDetermination => race_determination_screen(ctx, dem, scr),
-            Screen::RaceDisplay => race_display_screen(ctx, dem, scr),
-            Screen::ClassDetermination => class_determination_screen(ctx, dem, scr),
-            Screen::ClassDisplay => class_display_screen(ctx, dem, scr),
-            Screen::FactDisplay => fact_screen(ctx, dem, scr),
-            Screen::MissedFactDisplay => missed_fact_screen(ctx, dem, scr,),
-            Screen::End => end_screen(ctx, dem, scr)
-        }   
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.show(ctx, frame.info().native_pixels_per_point);
     }
 }
 
+fn nav_bar(ctx: &egui::Context, nav: &mut Navigator, lang: &mut Lang, view: &mut View, demo: &mut demographic::Demo, screen: &mut Screen) {
+    egui::TopBottomPanel::bottom("nav_bar").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            let back = ui.add_enabled(nav.can_back(), egui::Button::new(lang.tr("nav.back")));
+            if back.on_hover_text(lang.tr("nav.back.hover")).clicked() {
+                let from = *screen;
+                if nav.back(screen, demo) {
+                    tracing::debug!(?from, to = ?*screen, "went back");
+                }
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if nav.facilitator {
+                    ui.strong(lang.tr("nav.facilitator")).on_hover_text(lang.tr("nav.facilitator.hover"));
+                    ui.separator();
+                }
+
+                if ui.selectable_label(view.is_projector(), lang.tr("nav.projector"))
+                    .on_hover_text(lang.tr("nav.projector.hover"))
+                    .clicked()
+                {
+                    view.toggle_projector();
+                }
+
+                ui.separator();
+
+                ui.checkbox(&mut view.high_contrast, lang.tr("nav.contrast"))
+                    .on_hover_text(lang.tr("nav.contrast.hover"));
+
+                ui.separator();
+
+                let current = *lang;
+                let languages = egui::ComboBox::from_id_source("lang")
+                    .selected_text(current.name())
+                    .show_ui(ui, |ui| {
+                        for option in Lang::ALL {
+                            ui.selectable_value(lang, option, option.name());
+                        }
+                    });
+                a11y::set_name(ui, &languages.response, current.tr("lang.label"));
+                ui.label(current.tr("lang.label"));
+            });
+        });
+    });
+}
+
 /*
 fn fact_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen: &mut Screen) {
     egui::SidePanel::left("stat_panel").show(ctx, |ui| {
@@ -165,34 +409,220 @@ fn fact_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen: &mut S
     });
 }
 */
-fn start_screen(ctx: &egui::Context, screen: &mut Screen) {
+fn start_screen(ctx: &egui::Context, lang: Lang, bad_code: bool, quiz: &mut Quiz, analytics: &mut Analytics, surveys: &mut Surveys) -> Option<Action> {
+    let mut action = None;
+
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-            ui.add_space(180.0);
-            ui.heading("The Vocar");
-            ui.label("Welcome to the Vocar! This is an activity that guests participated in at the Bob Moses Conference 2023.");
+            layout::top_space(ui);
+            a11y::heading(ui, lang.tr("start.heading"), false);
+            if bad_code {
+                ui.colored_label(ui.visuals().warn_fg_color, lang.tr("share.error"));
+            }
+            ui.label(lang.tr("start.welcome"));
             ui.add_space(20.0);
-            if ui.button("Begin Your Journey!").on_hover_text("Click to go to the next screen.").clicked() {
-                *screen = Screen::RaceDetermination;
+            if a11y::primary_button(ui, lang.tr("start.begin"), lang.tr("common.next.hover")).clicked() {
+                if surveys.enabled {
+                    surveys.begin(analytics::now());
+                }
+                quiz.clear();
+                action = Some(Action::Next);
+            }
+            ui.checkbox(&mut quiz.enabled, lang.tr("quiz.enabled")).on_hover_text(lang.tr("quiz.enabled.hover"));
+            ui.add_space(10.0);
+            if ui.button(lang.tr("start.classroom")).on_hover_text(lang.tr("start.classroom.hover")).clicked() {
+                action = Some(Action::Classroom);
+            }
+            ui.add_space(10.0);
+            if ui.button(lang.tr("start.lineage")).on_hover_text(lang.tr("start.lineage.hover")).clicked() {
+                action = Some(Action::Lineage);
             }
+            ui.add_space(20.0);
+            usage_log(ui, lang, analytics);
+            reflection_surveys(ui, lang, surveys);
         });
 
         ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
             egui::warn_if_debug_build(ui);
         });
     });
+
+    action
 }
 
-fn race_determination_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen: &mut Screen) {
+/// Switching the anonymous usage log on and off, and handing it to the people evaluating the activity.
+fn usage_log(ui: &mut egui::Ui, lang: Lang, analytics: &mut Analytics) {
+    let id = ui.make_persistent_id("usage_log_saved");
+
+    egui::CollapsingHeader::new(lang.tr("analytics.heading")).show(ui, |ui| {
+        ui.checkbox(&mut analytics.enabled, lang.tr("analytics.enabled"))
+            .on_hover_text(lang.tr("analytics.enabled.hover"));
+        ui.label(lang.trf("analytics.count", &[("count", &analytics.sessions.len())]));
+
+        ui.horizontal(|ui| {
+            let export = ui.add_enabled(!analytics.sessions.is_empty(), egui::Button::new(lang.tr("analytics.export")));
+            if export.on_hover_text(lang.tr("analytics.export.hover")).clicked() {
+                let saved = analytics
+                    .to_json()
+                    .map_err(std::io::Error::other)
+                    .and_then(|json| download::save("vocar-usage.json", "application/json", json.as_bytes()));
+                let message = match saved {
+                    Ok(place) => lang.trf("analytics.saved", &[("place", &place)]),
+                    Err(error) => lang.trf("analytics.save_failed", &[("error", &error)]),
+                };
+                ui.data_mut(|data| data.insert_temp(id, message));
+            }
+
+            let clear = ui.add_enabled(!analytics.sessions.is_empty(), egui::Button::new(lang.tr("analytics.clear")));
+            if clear.on_hover_text(lang.tr("analytics.clear.hover")).clicked() {
+                analytics.clear();
+                ui.data_mut(|data| data.remove::<String>(id));
+            }
+        });
+
+        if let Some(message) = ui.data_mut(|data| data.get_temp::<String>(id)) {
+            ui.label(message);
+        }
+    });
+}
+
+/// Switching the reflection surveys on and off, and handing the answers to the researchers.
+fn reflection_surveys(ui: &mut egui::Ui, lang: Lang, surveys: &mut Surveys) {
+    let id = ui.make_persistent_id("reflection_surveys_saved");
+
+    egui::CollapsingHeader::new(lang.tr("survey.heading")).show(ui, |ui| {
+        ui.checkbox(&mut surveys.enabled, lang.tr("survey.enabled"))
+            .on_hover_text(lang.tr("survey.enabled.hover"));
+        ui.label(lang.trf("survey.count", &[("count", &surveys.responses.len())]));
+
+        ui.horizontal(|ui| {
+            let export = ui.add_enabled(!surveys.responses.is_empty(), egui::Button::new(lang.tr("survey.export")));
+            if export.on_hover_text(lang.tr("survey.export.hover")).clicked() {
+                let csv = surveys.to_csv(surveys.survey());
+                let message = match download::save("vocar-surveys.csv", "text/csv", csv.as_bytes()) {
+                    Ok(place) => lang.trf("survey.saved", &[("place", &place)]),
+                    Err(error) => lang.trf("survey.save_failed", &[("error", &error)]),
+                };
+                ui.data_mut(|data| data.insert_temp(id, message));
+            }
+
+            let clear = ui.add_enabled(!surveys.responses.is_empty(), egui::Button::new(lang.tr("survey.clear")));
+            if clear.on_hover_text(lang.tr("survey.clear.hover")).clicked() {
+                surveys.clear();
+                ui.data_mut(|data| data.remove::<String>(id));
+            }
+        });
+
+        if let Some(message) = ui.data_mut(|data| data.get_temp::<String>(id)) {
+            ui.label(message);
+        }
+    });
+}
+
+/// A question for every one of `items`, filling in `answers`.
+fn survey_form(ui: &mut egui::Ui, lang: Lang, items: &[Item], answers: &mut Answers) {
+    for item in items {
+        ui.add_space(10.0);
+        ui.strong(lang.tr(&item.text));
+
+        match &item.kind {
+            Kind::Likert => {
+                let mut picked = match answers.get(&item.id) {
+                    Some(Answer::Likert(point)) => Some(*point),
+                    _ => None,
+                };
+                ui.horizontal_wrapped(|ui| {
+                    for point in 1..=Survey::LIKERT_POINTS {
+                        ui.radio_value(&mut picked, Some(point), lang.tr(&format!("survey.likert.{point}")));
+                    }
+                });
+                if let Some(point) = picked {
+                    answers.insert(item.id.clone(), Answer::Likert(point));
+                }
+            }
+            Kind::Choice(options) => {
+                let mut picked = match answers.get(&item.id) {
+                    Some(Answer::Choice(index)) => Some(*index),
+                    _ => None,
+                };
+                for (index, option) in options.iter().enumerate() {
+                    ui.radio_value(&mut picked, Some(index), lang.tr(option));
+                }
+                if let Some(index) = picked {
+                    answers.insert(item.id.clone(), Answer::Choice(index));
+                }
+            }
+            Kind::Text => {
+                let mut text = match answers.get(&item.id) {
+                    Some(Answer::Text(text)) => text.clone(),
+                    _ => String::new(),
+                };
+                let response = ui.add(egui::TextEdit::multiline(&mut text).desired_rows(2));
+                a11y::set_name(ui, &response, lang.tr(&item.text));
+                if response.changed() {
+                    answers.insert(item.id.clone(), Answer::Text(text));
+                }
+            }
+        }
+    }
+}
+
+fn pre_survey_screen(ctx: &egui::Context, lang: Lang, surveys: &mut Surveys) -> Option<Action> {
+    let mut action = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            a11y::heading(ui, lang.tr("survey.before.heading"), false);
+            ui.label(lang.tr("survey.before.body"));
+
+            let survey = surveys.survey().clone();
+            survey_form(ui, lang, &survey.before, &mut surveys.current(analytics::now()).before);
+
+            ui.add_space(20.0);
+            if a11y::primary_button(ui, lang.tr("survey.continue"), lang.tr("common.next.hover")).clicked() {
+                action = Some(Action::Next);
+            }
+        });
+    });
+
+    action
+}
+
+fn post_survey_screen(ctx: &egui::Context, lang: Lang, surveys: &mut Surveys, demo: &mut demographic::Demo) -> Option<Action> {
+    let mut action = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            a11y::heading(ui, lang.tr("survey.after.heading"), false);
+            ui.label(lang.tr("survey.after.body"));
+
+            let survey = surveys.survey().clone();
+            survey_form(ui, lang, &survey.after, &mut surveys.current(analytics::now()).after);
+
+            ui.add_space(20.0);
+            if a11y::primary_button(ui, lang.tr("survey.continue"), lang.tr("common.next.hover")).clicked() {
+                surveys.finish(analytics::now(), demo);
+                demo.reset();
+                action = Some(Action::Next);
+            }
+        });
+    });
+
+    action
+}
+
+fn race_determination_screen(ctx: &egui::Context, lang: Lang, demo: &mut demographic::Demo) -> Option<Action> {
+    let mut action = None;
+
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-            ui.add_space(180.0);
-            ui.heading("Getting A Race");
-            ui.label("For this experience, you will be given a random race.");
+            layout::top_space(ui);
+            a11y::heading(ui, lang.tr("race_determination.heading"), false);
+            ui.label(lang.tr("race_determination.body"));
             ui.add_space(20.0);
-            if ui.button("Get My Race!").on_hover_text("Click to go to the next screen.").clicked() {
-                demo.race = Some(rand::random());
-                *screen = Screen::RaceDisplay;
+            if a11y::primary_button(ui, lang.tr("race_determination.button"), lang.tr("common.next.hover")).clicked() {
+                demo.draw_race();
+                action = Some(Action::Next);
             }
         });
 
@@ -200,17 +630,21 @@ fn race_determination_screen(ctx: &egui::Context, demo: &mut demographic::Demo,
             egui::warn_if_debug_build(ui);
         });
     });
+
+    action
 }
 
-fn race_display_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen: &mut Screen) {
+fn race_display_screen(ctx: &egui::Context, lang: Lang, demo: &demographic::Demo) -> Option<Action> {
+    let mut action = None;
+
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-            ui.add_space(180.0);
-            ui.heading(&format!("You are {} :).", demo.race.unwrap()));
-            ui.label("This will be your race PERMANENTLY.");
+            layout::top_space(ui);
+            a11y::heading(ui, lang.trf("race_display.heading", &[("race", &demo.race.unwrap().localize(lang))]), true);
+            ui.label(lang.tr("race_display.body"));
             ui.add_space(20.0);
-            if ui.button("Next!").on_hover_text("Click to go to the next screen.").clicked() {
-                *screen = Screen::ClassDetermination;
+            if a11y::primary_button(ui, lang.tr("race_display.button"), lang.tr("common.next.hover")).clicked() {
+                action = Some(Action::Next);
             }
         });
 
@@ -218,29 +652,38 @@ fn race_display_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen
             egui::warn_if_debug_build(ui);
         });
     });
+
+    action
 }
 
-fn class_determination_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen: &mut Screen) {
+fn class_determination_screen(ctx: &egui::Context, lang: Lang, demo: &mut demographic::Demo, quiz: &mut Quiz) -> Option<Action> {
+    let mut action = None;
+
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-            ui.add_space(180.0);
+            layout::top_space(ui);
             if demo.class_zero.is_none() {
-                ui.heading("Getting Your Starting Quintile");
-                ui.label("For this experience, you will also be given a random starting quintile.");
+                a11y::heading(ui, lang.tr("class_determination.start.heading"), false);
+                ui.label(lang.tr("class_determination.start.body"));
                 ui.add_space(20.0);
-                if ui.button("Starting Quintile!").on_hover_text("Click to go to the next screen.").clicked() {
-                    demo.class_zero = Some(rand::random());
-                    demo.class_n = demo.class_zero;
-                    *screen = Screen::ClassDisplay;
+                if a11y::primary_button(ui, lang.tr("class_determination.start.button"), lang.tr("common.next.hover")).clicked() {
+                    demo.draw_next();
+                    action = Some(Action::Next);
                 }
             } else {
-                ui.heading("Getting Your Next Quintile");
-                ui.label("Your current Quintile has the current chances of change:");
-                ui.label("Placeholde %'s");
+                a11y::heading(ui, lang.tr("class_determination.next.heading"), false);
+                ui.label(lang.tr("class_determination.next.body"));
+                ui.label(lang.tr("class_determination.next.placeholder"));
+                era_note(ui, lang, demo.history.len() + 1);
+                if quiz.enabled && demo.chances().is_some() {
+                    guess_form(ui, lang, &mut quiz.guess);
+                }
                 ui.add_space(20.0);
-                if ui.button("New Quintile!").on_hover_text("Click to go to the next screen.").clicked() {
-                    demo.next_gen();
-                    *screen = Screen::ClassDisplay;
+                if a11y::primary_button(ui, lang.tr("class_determination.next.button"), lang.tr("common.next.hover")).clicked() {
+                    let before = demo.clone();
+                    demo.draw_next();
+                    quiz.record(&before, demo);
+                    action = Some(Action::Next);
                 }
             }
         });
@@ -249,24 +692,39 @@ fn class_determination_screen(ctx: &egui::Context, demo: &mut demographic::Demo,
             egui::warn_if_debug_build(ui);
         });
     });
+
+    action
 }
 
-fn class_display_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen: &mut Screen) {
+fn class_display_screen(ctx: &egui::Context, lang: Lang, demo: &demographic::Demo, quiz: &Quiz) -> Option<Action> {
+    let mut action = None;
+
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-            ui.add_space(170.0);
-            ui.heading(&format!("You are in the {}.", demo.class_n.unwrap()));
+            layout::top_space(ui);
+            a11y::heading(ui, match demo.class_n.unwrap() {
+                demographic::Class::Incarcerated => lang.tr("class_display.heading.incarcerated").to_string(),
+                class => lang.trf("class_display.heading", &[("class", &class.localize(lang))]),
+            }, true);
             ui.add_space(10.0);
-            if demo.history.len() == 0 {
-                ui.label("This starting Quintile is just a jumpoff point and may change for each subsequent generation.");
-                ui.label("The chance to get a higher, equal or lower Quintile is not same.");
+            if let Some(beat) = story::beat(lang, demo, demo.history.len()) {
+                ui.label(egui::RichText::new(beat).italics());
+                ui.add_space(10.0);
+            }
+            if demo.history.is_empty() {
+                ui.label(lang.tr("class_display.start.body"));
+                ui.label(lang.tr("class_display.chances"));
             } else {
-                ui.label("This is your new Quintile for the nth Generation.");
-                ui.label("The chance to get a higher, equal or lower Quintile is not same.");
+                ui.label(lang.trf("class_display.next.body", &[("generation", &demo.history.len())]));
+                ui.label(lang.tr("class_display.chances"));
+                era_note(ui, lang, demo.history.len());
+                if let Some(prediction) = quiz.prediction(demo.history.len()).filter(|_| quiz.enabled) {
+                    reveal(ui, lang, prediction);
+                }
             }
             ui.add_space(20.0);
-            if ui.button("Next").on_hover_text("Click to go to the next screen.").clicked() {
-                *screen = Screen::FactDisplay;
+            if a11y::primary_button(ui, lang.tr("class_display.button"), lang.tr("common.next.hover")).clicked() {
+                action = Some(Action::Next);
             }
         });
 
@@ -274,101 +732,824 @@ fn class_display_screen(ctx: &egui::Context, demo: &mut demographic::Demo, scree
             egui::warn_if_debug_build(ui);
         });
     });
+
+    action
 }
 
-fn fact_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen: &mut Screen) {
-    egui::SidePanel::right("stat_sheet").resizable(false).default_width(400.0).show(ctx, |ui| {
-        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-            ui.heading("Your Vocar Card");
+fn fact_screen(ctx: &egui::Context, lang: Lang, demo: &demographic::Demo) -> Option<Action> {
+    let mut action = None;
+
+    if layout::is_narrow(ctx) {
+        egui::TopBottomPanel::bottom("stat_sheet_stacked").resizable(true).show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| vocar_card(ui, lang, demo));
         });
-        ui.separator();
-        ui.add_space(5.0);
-        ui.horizontal(|ui| {
-            ui.label("Race: ");
-            ui.text_edit_singleline(&mut
-                if let Some(r) = demo.race {
-                    r.to_string()
-                } else {
-                    String::new()
-                }
-            );
+    } else {
+        let width = ctx.screen_rect().width() / 3.0;
+        egui::SidePanel::right("stat_sheet").default_width(width).show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| vocar_card(ui, lang, demo));
         });
+    }
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        if a11y::primary_button(ui, lang.tr("fact.button"), lang.tr("common.next.hover")).clicked() {
+            action = Some(Action::Next);
+        }
+    });
+
+    action
+}
+
+fn vocar_card(ui: &mut egui::Ui, lang: Lang, demo: &demographic::Demo) {
+    let text = |class: Option<demographic::Class>| class.map(|c| c.localize(lang)).unwrap_or_default();
+
+    ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+        a11y::heading(ui, lang.tr("card.heading"), false);
+    });
+    ui.separator();
+    ui.add_space(5.0);
+    a11y::field(ui, lang.tr("card.race"), &demo.race.map(|r| r.localize(lang)).unwrap_or_default());
+    ui.add_space(10.0);
+    a11y::field(ui, lang.tr("card.current_class"), &text(demo.class_n));
+    ui.add_space(5.0);
+    ui.separator();
+    ui.add_space(5.0);
+    a11y::field(ui, lang.tr("card.starting_class"), &text(demo.class_zero));
+    ui.add_space(10.0);
+    a11y::field(ui, lang.tr("card.final_class"), &text(demo.history.last().copied().filter(|_| demo.is_finished())));
+    ui.add_space(5.0);
+    ui.separator();
+    ui.add_space(5.0);
+    for (i, (class, era)) in demo.history.iter().zip(demo.eras()).enumerate() {
+        let label = lang.trf("card.generation.era", &[("generation", &(i + 1)), ("era", &era.localize(lang))]);
+        a11y::field(ui, &label, &class.localize(lang));
         ui.add_space(10.0);
-        ui.horizontal(|ui| {
-            ui.label("Current Class: ");
-            ui.text_edit_singleline(&mut
-                if let Some(c) = demo.class_n {
-                    c.to_string()
+    }
+}
+
+/// Asks what the participant expects of the next draw.
+fn guess_form(ui: &mut egui::Ui, lang: Lang, guess: &mut Guess) {
+    ui.add_space(10.0);
+    ui.strong(lang.tr("quiz.guess.class"));
+    ui.horizontal_wrapped(|ui| {
+        for class in (1..=5).map(demographic::Class::Quintile).chain([demographic::Class::Incarcerated]) {
+            ui.radio_value(&mut guess.class, Some(class), class.localize(lang));
+        }
+    });
+
+    ui.add_space(5.0);
+    ui.strong(lang.tr("quiz.guess.incarceration"));
+    let mut percent = guess.incarceration.map_or(0.0, |chance| chance * 100.0);
+    let response = ui.add(egui::Slider::new(&mut percent, 0.0..=100.0).suffix("%").fixed_decimals(0));
+    a11y::set_name(ui, &response, lang.tr("quiz.guess.incarceration"));
+    if response.changed() {
+        guess.incarceration = Some(percent / 100.0);
+    }
+}
+
+/// The real chances of a draw, next to what was guessed for it.
+fn reveal(ui: &mut egui::Ui, lang: Lang, prediction: &Prediction) {
+    let percent = |chance: f64| format!("{:.0}", chance * 100.0);
+
+    ui.add_space(10.0);
+    ui.strong(lang.tr("quiz.reveal.heading"));
+    if let Some(class) = prediction.guess.class {
+        ui.label(lang.trf(
+            "quiz.reveal.class",
+            &[("class", &class.localize(lang)), ("percent", &percent(prediction.chance(class)))],
+        ));
+    }
+    if let Some(estimate) = prediction.guess.incarceration {
+        ui.label(lang.trf(
+            "quiz.reveal.incarceration",
+            &[("estimate", &percent(estimate)), ("percent", &percent(prediction.chance(demographic::Class::Incarcerated)))],
+        ));
+    }
+
+    egui::Grid::new("quiz_chances").striped(true).show(ui, |ui| {
+        for class in (1..=5).map(demographic::Class::Quintile).chain([demographic::Class::Incarcerated]) {
+            let chance = lang.trf("quiz.reveal.percent", &[("percent", &percent(prediction.chance(class)))]);
+            if class == prediction.drawn {
+                ui.strong(lang.trf("quiz.reveal.drawn", &[("class", &class.localize(lang))]));
+                ui.strong(chance);
+            } else {
+                ui.label(class.localize(lang));
+                ui.label(chance);
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/// How the journey's guesses compared with the chances they were drawn with.
+fn prediction_summary(ui: &mut egui::Ui, lang: Lang, quiz: &Quiz) {
+    if !quiz.enabled || quiz.predictions.is_empty() {
+        return;
+    }
+    let score = quiz.score();
+
+    ui.add_space(10.0);
+    ui.strong(lang.tr("quiz.summary.heading"));
+    if score.guessed > 0 {
+        let counts: [(&str, &dyn std::fmt::Display); 3] =
+            [("likeliest", &score.likeliest), ("came_true", &score.came_true), ("guessed", &score.guessed)];
+        ui.label(lang.trf("quiz.summary.classes", &counts));
+    }
+    if let Some(error) = score.incarceration_error {
+        ui.label(lang.trf("quiz.summary.incarceration", &[("points", &format!("{:.0}", error * 100.0))]));
+    }
+    ui.label(lang.tr(if score.likeliest * 2 >= score.guessed && score.incarceration_error.is_none_or(|error| error < 0.1) {
+        "quiz.summary.close"
+    } else {
+        "quiz.summary.far"
+    }));
+}
+
+/// Which [demographic::Era]'s chances `generation` is drawn with, and what that period was like.
+fn era_note(ui: &mut egui::Ui, lang: Lang, generation: usize) {
+    let era = demographic::Era::of_generation(generation);
+    // The baseline chances are the 2010s' own.
+    let own = era == demographic::Era::Tens || demographic::Eras::builtin().has_table(era);
+    let applies = if own { "era.applies" } else { "era.baseline" };
+    ui.add_space(10.0);
+    ui.strong(lang.trf(applies, &[("generation", &generation), ("era", &era.localize(lang))]));
+    ui.label(lang.tr(match era {
+        demographic::Era::Thirties => "era.thirties.about",
+        demographic::Era::Fifties => "era.fifties.about",
+        demographic::Era::Seventies => "era.seventies.about",
+        demographic::Era::Nineties => "era.nineties.about",
+        demographic::Era::Tens => "era.tens.about",
+    }));
+}
+
+/// "Generation `generation`" with its [demographic::Era], or the starting generation for 0.
+fn generation_label(lang: Lang, generation: usize) -> String {
+    match generation {
+        0 => lang.tr("end.counterfactual.start").to_string(),
+        i => lang.trf("generation.era", &[("generation", &i), ("era", &demographic::Era::of_generation(i).localize(lang))]),
+    }
+}
+
+fn missed_fact_screen(ctx: &egui::Context, lang: Lang, _demo: &demographic::Demo) -> Option<Action> {
+    let mut action = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        a11y::heading(ui, lang.tr("missed_fact.heading"), false);
+        if a11y::primary_button(ui, lang.tr("missed_fact.button"), lang.tr("common.next.hover")).clicked() {
+            action = Some(Action::Next);
+        }
+    });
+
+    action
+}
+
+fn end_screen(ctx: &egui::Context, lang: Lang, demo: &mut demographic::Demo, home: Option<&str>, asks_survey: bool, quiz: &Quiz) -> Option<Action> {
+    let mut action = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            a11y::heading(ui, lang.tr("end.heading"), false);
+
+            family_story(ui, lang, demo);
+            prediction_summary(ui, lang, quiz);
+            counterfactual(ui, lang, demo);
+            family_wealth(ui, lang, demo);
+
+            if let Ok(code) = share::encode(demo) {
+                // Without a page to link to, e.g. on desktop, the code itself is shared.
+                let link = home.map_or_else(|| code.clone(), |home| share::link(home, &code));
+
+                ui.add_space(10.0);
+                ui.strong(lang.tr("share.heading"));
+                ui.label(lang.tr("share.body"));
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut code.as_str()).code_editor());
+                    if ui.button(lang.tr("share.copy")).on_hover_text(lang.tr("share.copy.hover")).clicked() {
+                        ui.output_mut(|output| output.copied_text = link.clone());
+                    }
+                });
+                let response = qr_code(ui, &link, 200.0);
+                a11y::set_name(ui, &response, &lang.trf("share.qr", &[("link", &link)]));
+                ui.add_space(10.0);
+            }
+
+            save_card(ui, lang, demo);
+            ui.add_space(10.0);
+
+            if ui.button(lang.tr("scenarios.open")).on_hover_text(lang.tr("scenarios.open.hover")).clicked() {
+                action = Some(Action::Scenarios);
+            }
+            ui.add_space(10.0);
+
+            if a11y::primary_button(ui, lang.tr("end.button"), lang.tr("common.next.hover")).clicked() {
+                // The survey after the journey is handed in with it, so it is only reset after that.
+                if asks_survey {
+                    action = Some(Action::Next);
                 } else {
-                    String::new()
+                    demo.reset();
+                    action = Some(Action::Restart);
                 }
-            );
+            }
         });
+    });
+
+    action
+}
+
+/// The whole journey told as a story, a paragraph per generation.
+fn family_story(ui: &mut egui::Ui, lang: Lang, demo: &demographic::Demo) {
+    let beats = story::story(lang, demo);
+    if beats.is_empty() {
+        return;
+    }
+
+    ui.add_space(10.0);
+    ui.strong(lang.tr("story.heading"));
+    for beat in beats {
+        ui.label(beat);
         ui.add_space(5.0);
-        ui.separator();
+    }
+}
+
+/// The journey next to the same draws made with the other race's chances.
+fn counterfactual(ui: &mut egui::Ui, lang: Lang, demo: &demographic::Demo) {
+    let Some(race) = demo.race else {
+        return;
+    };
+    // Replaying is cheap, but the end screen is drawn every frame and the replay never changes.
+    let id = ui.make_persistent_id(("counterfactual", demo.seed, race, demo.draws()));
+    let other = ui.data_mut(|data| data.get_temp::<demographic::Demo>(id)).unwrap_or_else(|| {
+        let other = demo.as_race(race.other());
+        ui.data_mut(|data| data.insert_temp(id, other.clone()));
+        other
+    });
+
+    ui.add_space(10.0);
+    ui.strong(lang.trf("end.counterfactual.heading", &[("race", &race.other().localize(lang))]));
+    ui.label(lang.tr("end.counterfactual.body"));
+    ui.add_space(5.0);
+
+    egui::Grid::new("counterfactual").striped(true).show(ui, |ui| {
+        ui.label("");
+        ui.strong(lang.trf("end.counterfactual.you", &[("race", &race.localize(lang))]));
+        ui.strong(lang.trf("end.counterfactual.other", &[("race", &race.other().localize(lang))]));
+        ui.end_row();
+
+        let rows = demo.class_zero.iter().chain(&demo.history).zip(other.class_zero.iter().chain(&other.history));
+        for (i, (mine, theirs)) in rows.enumerate() {
+            ui.label(generation_label(lang, i));
+            ui.label(mine.localize(lang));
+            if mine == theirs {
+                ui.label(theirs.localize(lang));
+            } else {
+                ui.strong(theirs.localize(lang));
+            }
+            ui.end_row();
+        }
+    });
+    ui.add_space(10.0);
+}
+
+/// What the journey meant in money, next to the median families of each race. Closed at first.
+fn family_wealth(ui: &mut egui::Ui, lang: Lang, demo: &demographic::Demo) {
+    let table = Table::builtin();
+    let ledger = table.ledger(demo);
+    let Some(last) = ledger.last() else {
+        return;
+    };
+
+    egui::CollapsingHeader::new(lang.tr("wealth.heading")).id_source("family_wealth").show(ui, |ui| {
+        ui.label(lang.trf(
+            "wealth.body",
+            &[("savings", &format!("{:.0}", table.savings_rate * 100.0)), ("inheritance", &format!("{:.0}", table.inheritance * 100.0))],
+        ));
         ui.add_space(5.0);
-        ui.horizontal(|ui| {
-            ui.label("Starting Class: ");
-            ui.text_edit_singleline(&mut
-                if let Some(c) = demo.class_zero {
-                    c.to_string()
-                } else {
-                    String::new()
-                }
-            );
+
+        egui::Grid::new("wealth").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("");
+            ui.strong(lang.tr("wealth.earned"));
+            ui.strong(lang.tr("wealth.lost"));
+            ui.strong(lang.tr("wealth.wealth"));
+            ui.end_row();
+
+            for (i, generation) in ledger.iter().enumerate() {
+                ui.label(generation_label(lang, i));
+                ui.label(generation.class.localize(lang));
+                ui.label(lang.money(generation.earned));
+                ui.label(if generation.lost > 0.0 { lang.money(generation.lost) } else { "-".to_string() });
+                ui.label(lang.money(generation.wealth));
+                ui.end_row();
+            }
         });
-        ui.add_space(10.0);
-        ui.horizontal(|ui| {
-            ui.label("Final Class: ");
-            ui.text_edit_singleline(&mut
-                if let Some(c) = demo.class_five {
-                    c.to_string()
-                } else {
-                    String::new()
+        ui.add_space(5.0);
+
+        a11y::field(ui, &lang.trf("wealth.yours", &[("generations", &demographic::Demo::GENERATIONS)]), &lang.money(last.wealth));
+
+        // Simulating is too slow to redo every frame, and always gives the same medians.
+        let id = ui.make_persistent_id("wealth_medians");
+        let medians = ui.data_mut(|data| data.get_temp::<[f64; 2]>(id)).unwrap_or_else(|| {
+            let medians = [demographic::Race::Black, demographic::Race::White].map(|race| table.median(race, 10_000, 0));
+            ui.data_mut(|data| data.insert_temp(id, medians));
+            medians
+        });
+        for (race, median) in [demographic::Race::Black, demographic::Race::White].into_iter().zip(medians) {
+            a11y::field(ui, &lang.trf("wealth.median", &[("race", &race.localize(lang))]), &lang.money(median));
+        }
+    });
+    ui.add_space(10.0);
+}
+
+fn scenarios_screen(ctx: &egui::Context, lang: Lang, interventions: &mut Vec<Intervention>) -> Option<Action> {
+    let mut action = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+                a11y::heading(ui, lang.tr("scenarios.heading"), false);
+                ui.label(lang.tr("scenarios.body"));
+            });
+            ui.add_space(10.0);
+
+            for intervention in Intervention::ALL {
+                let mut on = interventions.contains(&intervention);
+                let hover = lang.tr(&format!("{}.hover", intervention.key())).to_string();
+                if ui.checkbox(&mut on, lang.tr(intervention.key())).on_hover_text(hover).changed() {
+                    if on {
+                        interventions.push(intervention);
+                    } else {
+                        interventions.retain(|&i| i != intervention);
+                    }
                 }
-            );
+            }
+            ui.add_space(10.0);
+
+            let id = ui.make_persistent_id("scenario_method");
+            let mut method = ui.data_mut(|data| data.get_temp(id)).unwrap_or(Method::Exact);
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut method, Method::Exact, lang.tr("scenarios.exact"))
+                    .on_hover_text(lang.tr("scenarios.exact.hover"));
+                let simulated = Method::MonteCarlo { runs: 10_000, seed: rand::random() };
+                if ui
+                    .radio(matches!(method, Method::MonteCarlo { .. }), lang.tr("scenarios.monte_carlo"))
+                    .on_hover_text(lang.tr("scenarios.monte_carlo.hover"))
+                    .clicked()
+                {
+                    method = simulated;
+                }
+                if let Method::MonteCarlo { runs, seed } = &mut method {
+                    ui.add(egui::DragValue::new(runs).clamp_range(100..=1_000_000).speed(100.0));
+                    if ui.button(lang.tr("scenarios.again")).on_hover_text(lang.tr("scenarios.again.hover")).clicked() {
+                        *seed = rand::random();
+                    }
+                }
+            });
+            ui.data_mut(|data| data.insert_temp(id, method));
+
+            // Simulating is too slow to redo every frame, so results are kept until something changes.
+            let results_id = ui.make_persistent_id("scenario_results");
+            let key = (interventions.clone(), method);
+            let comparisons = match ui.data_mut(|data| data.get_temp::<((Vec<Intervention>, Method), Vec<model::Comparison>)>(results_id)) {
+                Some((cached, comparisons)) if cached == key => comparisons,
+                _ => {
+                    let comparisons = model::compare(interventions, method);
+                    ui.data_mut(|data| data.insert_temp(results_id, (key, comparisons.clone())));
+                    comparisons
+                }
+            };
+
+            for comparison in &comparisons {
+                ui.add_space(10.0);
+                ui.strong(comparison.race.localize(lang));
+                share_chart(
+                    ui,
+                    lang,
+                    ("scenarios", comparison.race),
+                    [(lang.tr("scenarios.before"), comparison.before), (lang.tr("scenarios.after"), comparison.after)],
+                );
+            }
+
+            ui.add_space(10.0);
+            if a11y::primary_button(ui, lang.tr("scenarios.button"), lang.tr("common.next.hover")).clicked() {
+                action = Some(Action::Next);
+            }
         });
-        ui.add_space(5.0);
-        ui.separator();
-        ui.add_space(5.0);
-        for (i, class) in demo.history.iter().enumerate() {
+    });
+
+    action
+}
+
+fn lineage_screen(ctx: &egui::Context, lang: Lang, lineage: &mut Lineage) -> Option<Action> {
+    let mut action = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+                a11y::heading(ui, lang.tr("lineage.heading"), false);
+                ui.label(lang.tr("lineage.body"));
+            });
+            ui.add_space(10.0);
+
             ui.horizontal(|ui| {
-                let text: &str = &format!("Generation {}: ", i + 1);
-                ui.label(text);
-                ui.text_edit_singleline(&mut class.to_string());
-                ui.text_style_height(&egui::TextStyle::Button)
+                ui.label(lang.tr("lineage.children"));
+                ui.add(egui::DragValue::new(&mut lineage.children).clamp_range(1..=Lineage::MAX_CHILDREN));
+                ui.separator();
+                ui.label(lang.tr("lineage.start"));
+                ui.add(egui::DragValue::new(&mut lineage.start).clamp_range(1..=5));
+                ui.separator();
+                if ui.button(lang.tr("lineage.again")).on_hover_text(lang.tr("lineage.again.hover")).clicked() {
+                    lineage.seed = rand::random();
+                }
             });
             ui.add_space(10.0);
+            tree_legend(ui, lang);
+
+            // Growing both trees is too slow to redo every frame, so they are kept until the lineage
+            // changes. Shared, so taking them out of memory doesn't copy every person.
+            let id = ui.make_persistent_id("lineage_families");
+            let families = match ui.data_mut(|data| data.get_temp::<(Lineage, Arc<[(demographic::Race, Person); 2]>)>(id)) {
+                Some((cached, families)) if cached == *lineage => families,
+                _ => {
+                    let families =
+                        Arc::new([demographic::Race::Black, demographic::Race::White].map(|race| (race, lineage.grow(race))));
+                    ui.data_mut(|data| data.insert_temp(id, (*lineage, Arc::clone(&families))));
+                    families
+                }
+            };
+            for (race, family) in families.iter() {
+                ui.add_space(10.0);
+                ui.strong(race.localize(lang));
+                let last = family.generation(demographic::Demo::GENERATIONS);
+                a11y::field(ui, lang.tr("lineage.size"), &last.len().to_string());
+                a11y::field(ui, lang.tr("lineage.incarcerated"), &family.composition(demographic::Demo::GENERATIONS)[5].to_string());
+                family_tree(ui, lang, family);
+            }
+
+            ui.add_space(10.0);
+            ui.strong(lang.tr("lineage.last"));
+            let shares = |family: &Person| {
+                let counts = family.composition(demographic::Demo::GENERATIONS);
+                let total = counts.iter().sum::<usize>().max(1) as f64;
+                counts.map(|count| count as f64 / total)
+            };
+            let [(black, black_family), (white, white_family)] = &*families;
+            share_chart(
+                ui,
+                lang,
+                "lineage",
+                [(&black.localize(lang), shares(black_family)), (&white.localize(lang), shares(white_family))],
+            );
+
+            ui.add_space(10.0);
+            if a11y::primary_button(ui, lang.tr("lineage.button"), lang.tr("common.next.hover")).clicked() {
+                action = Some(Action::Next);
+            }
+        });
+    });
+
+    action
+}
+
+/// Colour of `class` in family trees, darker further up, red for incarceration.
+fn class_color(class: demographic::Class) -> egui::Color32 {
+    use egui::Color32;
+
+    match class {
+        demographic::Class::Quintile(1) => Color32::from_rgb(198, 219, 239),
+        demographic::Class::Quintile(2) => Color32::from_rgb(158, 202, 225),
+        demographic::Class::Quintile(3) => Color32::from_rgb(107, 174, 214),
+        demographic::Class::Quintile(4) => Color32::from_rgb(49, 130, 189),
+        demographic::Class::Quintile(_) => Color32::from_rgb(8, 81, 156),
+        demographic::Class::Incarcerated => Color32::from_rgb(203, 24, 29),
+    }
+}
+
+/// Which colour is which class in [family_tree].
+fn tree_legend(ui: &mut egui::Ui, lang: Lang) {
+    let classes = (1..=5).map(demographic::Class::Quintile).chain([demographic::Class::Incarcerated]);
+
+    ui.horizontal_wrapped(|ui| {
+        for class in classes {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+            ui.painter().rect_filled(rect, 2.0, class_color(class));
+            ui.label(class.localize(lang));
+            ui.add_space(5.0);
+        }
+    });
+}
+
+/// A family tree as one row per generation, everyone as wide as their share of their parent.
+fn family_tree(ui: &mut egui::Ui, lang: Lang, family: &Person) {
+    use egui::{pos2, vec2, Rect};
+
+    fn draw(painter: &egui::Painter, person: &Person, rect: Rect) {
+        // Too narrow to leave gaps between people in the bigger generations.
+        let gap = if rect.width() > 4.0 { 1.0 } else { 0.0 };
+        painter.rect_filled(rect.shrink2(vec2(gap, 1.0)), 0.0, class_color(person.class));
+
+        let width = rect.width() / person.children.len().max(1) as f32;
+        for (i, child) in person.children.iter().enumerate() {
+            let min = pos2(rect.left() + width * i as f32, rect.bottom());
+            draw(painter, child, Rect::from_min_size(min, vec2(width, rect.height())));
+        }
+    }
+
+    let row = 18.0;
+    let rows = demographic::Demo::GENERATIONS + 1;
+    let (response, painter) = ui.allocate_painter(vec2(ui.available_width(), row * rows as f32), egui::Sense::hover());
+    draw(&painter, family, Rect::from_min_size(response.rect.min, vec2(response.rect.width(), row)));
+
+    // Reads out what each generation is made of, since the tree itself is only colours.
+    let description: Vec<String> = (0..rows)
+        .map(|generation| {
+            let counts = family.composition(generation);
+            let classes: Vec<String> = counts
+                .iter()
+                .enumerate()
+                .filter(|(_, &count)| count > 0)
+                .map(|(i, count)| {
+                    let name = match i {
+                        5 => lang.tr("chart.incarcerated").to_string(),
+                        i => lang.trf("chart.quintile", &[("number", &(i + 1))]),
+                    };
+                    lang.trf("lineage.count", &[("name", &name), ("count", count)])
+                })
+                .collect();
+            let people = counts.iter().sum::<usize>();
+            format!("{} {}.", lang.trf("lineage.generation", &[("generation", &generation), ("count", &people)]), classes.join(", "))
+        })
+        .collect();
+    a11y::set_name(ui, &response, &description.join(" "));
+}
+
+/// Buttons saving the Vocar Card as a PNG or PDF, and where the last one went.
+fn save_card(ui: &mut egui::Ui, lang: Lang, demo: &demographic::Demo) {
+    let id = ui.make_persistent_id("card_saved");
+    let name = format!("vocar-card-{}", share::encode(demo).unwrap_or_default());
+
+    ui.horizontal(|ui| {
+        let png = ui.button(lang.tr("card.png")).on_hover_text(lang.tr("card.png.hover"));
+        let pdf = ui.button(lang.tr("card.pdf")).on_hover_text(lang.tr("card.pdf.hover"));
+
+        let saved = if png.clicked() {
+            Some(download::save(&format!("{name}.png"), "image/png", &card::png(lang, demo)))
+        } else if pdf.clicked() {
+            Some(download::save(&format!("{name}.pdf"), "application/pdf", &card::pdf(lang, demo)))
+        } else {
+            None
+        };
+
+        if let Some(saved) = saved {
+            let message = match saved {
+                Ok(place) => lang.trf("card.saved", &[("place", &place)]),
+                Err(error) => lang.trf("card.save_failed", &[("error", &error)]),
+            };
+            ui.data_mut(|data| data.insert_temp(id, message));
         }
     });
 
+    if let Some(message) = ui.data_mut(|data| data.get_temp::<String>(id)) {
+        ui.label(message);
+    }
+}
+
+/// `text` as a QR code, black on white whatever the theme so phones and printers pick it up.
+fn qr_code(ui: &mut egui::Ui, text: &str, size: f32) -> egui::Response {
+    use egui::{vec2, Color32, Rect};
+
+    let Ok(code) = qrcode::QrCode::new(text) else {
+        return ui.label(text);
+    };
+    // Scanners need a blank border four modules wide.
+    let (modules, border) = (code.width(), 4);
+    let module = size / (modules + 2 * border) as f32;
+
+    let (response, painter) = ui.allocate_painter(vec2(size, size), egui::Sense::hover());
+    painter.rect_filled(response.rect, 0.0, Color32::WHITE);
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color == qrcode::Color::Dark {
+            let (x, y) = ((i % modules + border) as f32, (i / modules + border) as f32);
+            let rect = Rect::from_min_size(response.rect.min + vec2(x, y) * module, vec2(module, module));
+            painter.rect_filled(rect, 0.0, Color32::BLACK);
+        }
+    }
+
+    response
+}
+
+fn shared_journey_screen(ctx: &egui::Context, lang: Lang, journey: &demographic::Demo) -> Option<Action> {
+    let mut action = None;
+
     egui::CentralPanel::default().show(ctx, |ui| {
-        if ui.button("Next!").on_hover_text("Click to go to the next screen.").clicked() {
-            if demo.history.len() < 5 {
-                *screen = Screen::ClassDetermination;
-            } else {
-                *screen = Screen::MissedFactDisplay;
+        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+            a11y::heading(ui, lang.tr("shared.heading"), true);
+            ui.label(lang.tr("shared.body"));
+        });
+        ui.add_space(10.0);
+
+        egui::ScrollArea::vertical().max_height(ui.available_height() - 60.0).show(ui, |ui| {
+            vocar_card(ui, lang, journey);
+        });
+
+        ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+            if a11y::primary_button(ui, lang.tr("shared.button"), lang.tr("shared.button.hover")).clicked() {
+                action = Some(Action::Restart);
             }
-            
-        }
+        });
     });
+
+    action
 }
 
-fn missed_fact_screen(ctx: &egui::Context, _demo: &mut demographic::Demo, screen: &mut Screen) {
+fn roster_setup_screen(ctx: &egui::Context, lang: Lang, roster: &mut Roster) -> Option<Action> {
+    let mut action = None;
+
     egui::CentralPanel::default().show(ctx, |ui| {
-        ui.label("MFS");
-        if ui.button("TODO").clicked() {
-            *screen = Screen::End
-        }
+        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+            layout::top_space(ui);
+            a11y::heading(ui, lang.tr("roster_setup.heading"), false);
+            let body = ui.label(lang.tr("roster_setup.body"));
+            ui.add_space(10.0);
+            ui.add(egui::TextEdit::multiline(&mut roster.draft).hint_text(lang.tr("roster_setup.hint")))
+                .labelled_by(body.id);
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                let id = ui.make_persistent_id("roster_count");
+                let mut count = ui.data_mut(|data| *data.get_temp_mut_or(id, 10_usize));
+                ui.add(egui::DragValue::new(&mut count).clamp_range(1..=100));
+                ui.data_mut(|data| data.insert_temp(id, count));
+
+                if ui.button(lang.tr("roster_setup.number")).on_hover_text(lang.tr("roster_setup.number.hover")).clicked() {
+                    roster.draft = (1..=count)
+                        .map(|n| lang.trf("roster_setup.numbered", &[("number", &n)]))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                }
+            });
+            ui.add_space(20.0);
+            if a11y::primary_button(ui, lang.tr("roster_setup.button"), lang.tr("common.next.hover")).clicked() {
+                roster.start_from_draft();
+                action = Some(Action::Next);
+            }
+        });
     });
+
+    action
 }
 
-fn end_screen(ctx: &egui::Context, demo: &mut demographic::Demo, screen: &mut Screen) {
+fn roster_draw_screen(ctx: &egui::Context, lang: Lang, roster: &mut Roster) -> Option<Action> {
+    let mut action = None;
+
+    egui::SidePanel::right("roster_sheet").default_width(ctx.screen_rect().width() / 3.0).show(ctx, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            a11y::heading(ui, lang.tr("roster_draw.everyone"), false);
+            ui.separator();
+            for participant in &roster.participants {
+                a11y::field(ui, &format!("{}: ", participant.name), &participant.demo.localize(lang));
+            }
+            ui.add_space(20.0);
+            dashboard(ui, lang, &stats::groups(roster.participants.iter().map(|p| &p.demo)));
+        });
+    });
+
     egui::CentralPanel::default().show(ctx, |ui| {
-        ui.label("ES");
-        if ui.button("TODO").clicked() {
-            demo.reset();
-            *screen = Screen::Start
-        }
+        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+            layout::top_space(ui);
+            ui.label(match roster.generation() {
+                Some(generation) => lang.trf("roster_draw.generation", &[("generation", &generation)]),
+                None if roster.stage() == 0 => lang.tr("roster_draw.races").to_string(),
+                None => lang.tr("roster_draw.starting").to_string(),
+            });
+            if let Some(participant) = roster.current() {
+                a11y::heading(ui, lang.trf("roster_draw.turn", &[("name", &participant.name)]), true);
+            }
+            ui.add_space(20.0);
+            if a11y::primary_button(ui, lang.tr("roster_draw.button"), lang.tr("roster_draw.button.hover")).clicked() {
+                roster.draw_current();
+                action = Some(Action::Next);
+            }
+        });
+    });
+
+    action
+}
+
+fn scoreboard_screen(ctx: &egui::Context, lang: Lang, roster: &Roster) -> Option<Action> {
+    let mut action = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+            a11y::heading(ui, lang.tr("scoreboard.heading"), true);
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for race in [demographic::Race::Black, demographic::Race::White] {
+                ui.add_space(10.0);
+                ui.strong(race.localize(lang));
+                egui::Grid::new(("scoreboard", race.localize(lang))).striped(true).show(ui, |ui| {
+                    ui.label(lang.tr("scoreboard.name"));
+                    ui.label(lang.tr("card.starting_class"));
+                    ui.label(lang.tr("card.current_class"));
+                    ui.end_row();
+
+                    for participant in roster.by_race(race) {
+                        let text = |class: Option<demographic::Class>| class.map(|c| c.localize(lang)).unwrap_or_default();
+
+                        ui.label(&participant.name);
+                        ui.label(text(participant.demo.class_zero));
+                        ui.label(text(participant.demo.class_n));
+                        ui.end_row();
+                    }
+                });
+            }
+            ui.add_space(20.0);
+            dashboard(ui, lang, &stats::groups(roster.participants.iter().map(|p| &p.demo)));
+        });
+
+        ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+            if a11y::primary_button(ui, lang.tr("scoreboard.button"), lang.tr("common.next.hover")).clicked() {
+                action = Some(Action::Restart);
+            }
+            if ui.button(lang.tr("scenarios.open")).on_hover_text(lang.tr("scenarios.open.hover")).clicked() {
+                action = Some(Action::Scenarios);
+            }
+        });
     });
-}
\ No newline at end of file
+
+    action
+}
+
+/// Everyone's current quintiles by race, next to the shares the weights predict.
+fn dashboard(ui: &mut egui::Ui, lang: Lang, groups: &[Group]) {
+    a11y::heading(ui, lang.tr("dashboard.heading"), false);
+    ui.separator();
+
+    for group in groups {
+        ui.add_space(10.0);
+        ui.strong(group.race.localize(lang));
+
+        if group.total() == 0 {
+            ui.label(lang.tr("dashboard.none"));
+            continue;
+        }
+
+        a11y::field(ui, lang.tr("dashboard.incarcerated"), &group.incarcerated().to_string());
+        let mobility = group.mobility.map_or_else(
+            || "-".to_string(),
+            |m| lang.trf("dashboard.mobility.value", &[("quintiles", &format!("{m:+.2}"))]),
+        );
+        a11y::field(ui, lang.tr("dashboard.mobility"), &mobility);
+
+        share_chart(
+            ui,
+            lang,
+            ("dashboard", group.race),
+            [(lang.tr("dashboard.drawn"), group.observed()), (lang.tr("dashboard.expected"), group.expected)],
+        );
+    }
+}
+
+/// Two sets of shares per quintile, then incarcerated, as bars next to each other.
+fn share_chart(ui: &mut egui::Ui, lang: Lang, id_source: impl std::hash::Hash, series: [(&str, [f64; 6]); 2]) {
+    use egui::plot::{Bar, BarChart, Legend, Plot};
+
+    let short = move |i: usize| match i {
+        5 => lang.tr("chart.incarcerated").to_string(),
+        i => lang.trf("chart.quintile", &[("number", &(i + 1))]),
+    };
+    let bars = |shares: [f64; 6], offset: f64| -> Vec<Bar> {
+        shares.iter().enumerate().map(|(i, share)| Bar::new(i as f64 + offset, share * 100.0).width(0.4)).collect()
+    };
+
+    let response = Plot::new(id_source)
+        .height(160.0)
+        .legend(Legend::default())
+        .allow_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(false)
+        .include_y(0.0)
+        .x_axis_formatter(move |x, _| {
+            let i = x.round();
+            if (x - i).abs() < 1e-6 && (0.0..6.0).contains(&i) { short(i as usize) } else { String::new() }
+        })
+        .show(ui, |plot| {
+            for ((name, shares), offset) in series.iter().zip([-0.2, 0.2]) {
+                plot.bar_chart(BarChart::new(bars(*shares, offset)).name(name));
+            }
+        })
+        .response;
+
+    // The chart is only pixels to a screen reader, so it reads out the numbers instead.
+    let description: Vec<String> = (0..6)
+        .map(|i| {
+            let shares: Vec<String> = series
+                .iter()
+                .map(|(name, shares)| {
+                    lang.trf("chart.share", &[("name", name), ("percent", &format!("{:.0}", shares[i] * 100.0))])
+                })
+                .collect();
+            format!("{}: {}.", short(i), shares.join(", "))
+        })
+        .collect();
+    a11y::set_name(ui, &response, &description.join(" "));
+}
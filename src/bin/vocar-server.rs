@@ -0,0 +1,10 @@
+//! Hosts shared Vocar sessions on the local network.
+//!
+//! Usage: `vocar-server [ADDRESS]`, where `ADDRESS` defaults to `0.0.0.0:8080`.
+
+fn main() -> std::io::Result<()> {
+    let addr = std::env::args().nth(1).unwrap_or_else(|| "0.0.0.0:8080".to_string());
+
+    println!("Serving Vocar sessions on http://{addr}");
+    vocar::server::Server::run(addr)
+}
@@ -0,0 +1,280 @@
+//! The Vocar Card as a picture or a printable page, so participants can take it with them.
+//!
+//! The card is laid out once as a list of [Mark]s, then drawn into a PNG or written out as a PDF.
+
+use std::io::{Cursor, Write};
+
+use crate::{
+    demographic::{Class, Demo, Era},
+    i18n::{Lang, Localize},
+    share,
+};
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+
+/// Size of the card in points, a 5 by 7.5 inch page.
+const WIDTH: f32 = 360.0;
+const HEIGHT: f32 = 540.0;
+
+/// Pixels per point in the PNG, so it prints sharply.
+const PNG_SCALE: f32 = 2.0;
+
+const INK: [u8; 3] = [20, 20, 20];
+const FAINT: [u8; 3] = [190, 190, 190];
+const PATH: [u8; 3] = [0, 70, 160];
+const INCARCERATED: [u8; 3] = [190, 20, 20];
+
+/// Something drawn on the card, measured in points from the top left.
+enum Mark {
+    /// `text` with its baseline starting at `x`, `y`.
+    Text { x: f32, y: f32, size: f32, text: String, color: [u8; 3] },
+    Rect { x: f32, y: f32, width: f32, height: f32, color: [u8; 3] },
+    Line { from: (f32, f32), to: (f32, f32), width: f32, color: [u8; 3] },
+}
+
+fn text(x: f32, y: f32, size: f32, text: impl Into<String>, color: [u8; 3]) -> Mark {
+    Mark::Text { x, y, size, text: text.into(), color }
+}
+
+fn layout(lang: Lang, demo: &Demo) -> Vec<Mark> {
+    let class_text = |class: Option<Class>| class.map(|c| c.localize(lang)).unwrap_or_default();
+    let mut marks = vec![
+        text(24.0, 44.0, 22.0, lang.tr("card.heading"), INK),
+        Mark::Line { from: (24.0, 58.0), to: (WIDTH - 24.0, 58.0), width: 1.0, color: INK },
+        text(24.0, 84.0, 13.0, format!("{}{}", lang.tr("card.race"), demo.race.map(|r| r.localize(lang)).unwrap_or_default()), INK),
+        text(24.0, 106.0, 13.0, format!("{}{}", lang.tr("card.starting_class"), class_text(demo.class_zero)), INK),
+    ];
+
+    for (i, class) in demo.history.iter().enumerate() {
+        let y = 130.0 + 20.0 * i as f32;
+        let era = Era::of_generation(i + 1).localize(lang);
+        let row = format!("{}{}", lang.trf("card.generation.era", &[("generation", &(i + 1)), ("era", &era)]), class.localize(lang));
+        if matches!(class, Class::Incarcerated) {
+            marks.push(Mark::Rect { x: 24.0, y: y - 9.0, width: 9.0, height: 9.0, color: INCARCERATED });
+            marks.push(text(40.0, y, 13.0, row, INCARCERATED));
+        } else {
+            marks.push(text(40.0, y, 13.0, row, INK));
+        }
+    }
+
+    marks.extend(chart(lang, demo, 250.0));
+
+    if let Ok(code) = share::encode(demo) {
+        marks.push(text(24.0, HEIGHT - 24.0, 10.0, lang.trf("card.code", &[("code", &code)]), INK));
+    }
+
+    marks
+}
+
+/// The quintile of every generation as a line, with incarceration as its own row at the bottom.
+fn chart(lang: Lang, demo: &Demo, top: f32) -> Vec<Mark> {
+    let (left, right, bottom) = (64.0, WIDTH - 24.0, top + 200.0);
+    let plot_top = top + 20.0;
+    // Rows from the top: Q5 down to Q1, then incarcerated.
+    let row_y = |row: usize| plot_top + (bottom - plot_top) * row as f32 / 5.0;
+    let column_x = |column: usize| left + (right - left) * column as f32 / Demo::GENERATIONS as f32;
+
+    let mut marks = vec![text(24.0, top, 13.0, lang.tr("card.chart"), INK)];
+
+    for row in 0..=5 {
+        let label = match row {
+            5 => lang.tr("chart.incarcerated").to_string(),
+            row => lang.trf("chart.quintile", &[("number", &(5 - row))]),
+        };
+        let color = if row == 5 { INCARCERATED } else { INK };
+        marks.push(Mark::Line { from: (left, row_y(row)), to: (right, row_y(row)), width: 0.5, color: FAINT });
+        marks.push(text(24.0, row_y(row) + 4.0, 10.0, label, color));
+    }
+    for column in 0..=Demo::GENERATIONS {
+        let label = match column {
+            0 => lang.tr("card.chart.start").to_string(),
+            column => column.to_string(),
+        };
+        // Roughly centred, taking a character to be half as wide as it is tall.
+        let half_width = 2.5 * label.chars().count() as f32;
+        marks.push(text(column_x(column) - half_width, bottom + 18.0, 10.0, label, INK));
+    }
+
+    let points: Vec<(f32, f32, bool)> = demo
+        .class_zero
+        .iter()
+        .chain(&demo.history)
+        .enumerate()
+        .map(|(column, class)| match class {
+            Class::Quintile(x) => (column_x(column), row_y(5 - *x as usize), false),
+            Class::Incarcerated => (column_x(column), row_y(5), true),
+        })
+        .collect();
+
+    for pair in points.windows(2) {
+        let ((x1, y1, _), (x2, y2, _)) = (pair[0], pair[1]);
+        marks.push(Mark::Line { from: (x1, y1), to: (x2, y2), width: 2.0, color: PATH });
+    }
+    for (x, y, incarcerated) in points {
+        let color = if incarcerated { INCARCERATED } else { PATH };
+        marks.push(Mark::Rect { x: x - 4.0, y: y - 4.0, width: 8.0, height: 8.0, color });
+    }
+
+    marks
+}
+
+/// The card as a PNG image.
+///
+/// # Example
+/// ```
+/// use vocar::{card, Demo, Lang};
+///
+/// let mut demo = Demo::new();
+/// while !demo.is_finished() {
+///     demo.draw_next();
+/// }
+///
+/// assert!(card::png(Lang::English, &demo).starts_with(b"\x89PNG"));
+/// assert!(card::pdf(Lang::English, &demo).starts_with(b"%PDF"));
+/// ```
+#[must_use]
+pub fn png(lang: Lang, demo: &Demo) -> Vec<u8> {
+    let (width, height) = ((WIDTH * PNG_SCALE) as u32, (HEIGHT * PNG_SCALE) as u32);
+    let mut image = image::RgbaImage::from_pixel(width, height, image::Rgba([255, 255, 255, 255]));
+
+    let fonts = egui::FontDefinitions::default();
+    let font = FontRef::try_from_slice(&fonts.font_data["Ubuntu-Light"].font).expect("egui's default font loads");
+
+    let mut fill = |x: f32, y: f32, color: [u8; 3], coverage: f32| {
+        if x < 0.0 || y < 0.0 || x >= width as f32 || y >= height as f32 {
+            return;
+        }
+        let pixel = image.get_pixel_mut(x as u32, y as u32);
+        for (channel, target) in pixel.0.iter_mut().zip(color) {
+            *channel = (f32::from(*channel) + (f32::from(target) - f32::from(*channel)) * coverage.min(1.0)) as u8;
+        }
+    };
+
+    for mark in layout(lang, demo) {
+        match mark {
+            Mark::Text { x, y, size, text, color } => {
+                let scaled = font.as_scaled(PxScale::from(size * PNG_SCALE));
+                let mut caret = x * PNG_SCALE;
+                let mut previous = None;
+                for c in text.chars() {
+                    let id = scaled.glyph_id(c);
+                    if let Some(previous) = previous {
+                        caret += scaled.kern(previous, id);
+                    }
+                    let glyph = id.with_scale_and_position(size * PNG_SCALE, ab_glyph::point(caret, y * PNG_SCALE));
+                    caret += scaled.h_advance(id);
+                    previous = Some(id);
+
+                    if let Some(outline) = font.outline_glyph(glyph) {
+                        let bounds = outline.px_bounds();
+                        outline.draw(|gx, gy, coverage| {
+                            fill(bounds.min.x + gx as f32, bounds.min.y + gy as f32, color, coverage);
+                        });
+                    }
+                }
+            }
+            Mark::Rect { x, y, width, height, color } => {
+                for py in (y * PNG_SCALE) as u32..((y + height) * PNG_SCALE) as u32 {
+                    for px in (x * PNG_SCALE) as u32..((x + width) * PNG_SCALE) as u32 {
+                        fill(px as f32, py as f32, color, 1.0);
+                    }
+                }
+            }
+            Mark::Line { from, to, width, color } => {
+                let (dx, dy) = ((to.0 - from.0) * PNG_SCALE, (to.1 - from.1) * PNG_SCALE);
+                let steps = dx.abs().max(dy.abs()).ceil().max(1.0) as u32;
+                // Stamp a square as thick as the line at every pixel along it.
+                let thickness = (width * PNG_SCALE).round().max(1.0);
+                for step in 0..=steps {
+                    let t = step as f32 / steps as f32;
+                    let x = (from.0 * PNG_SCALE + dx * t - thickness / 2.0).round();
+                    let y = (from.1 * PNG_SCALE + dy * t - thickness / 2.0).round();
+                    for offset_y in 0..thickness as u32 {
+                        for offset_x in 0..thickness as u32 {
+                            fill(x + offset_x as f32, y + offset_y as f32, color, 1.0);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut bytes = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+        .expect("PNGs can always be written to memory");
+    bytes
+}
+
+/// `text` as a PDF string in the standard fonts' Latin-1 encoding. Other characters become `?`.
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut bytes = vec![b'('];
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => bytes.extend([b'\\', c as u8]),
+            c => bytes.push(u8::try_from(u32::from(c)).unwrap_or(b'?')),
+        }
+    }
+    bytes.push(b')');
+    bytes
+}
+
+fn pdf_color(color: [u8; 3]) -> String {
+    let [r, g, b] = color.map(|channel| f32::from(channel) / 255.0);
+    format!("{r:.3} {g:.3} {b:.3}")
+}
+
+/// The card as a single page PDF, using the built-in Helvetica font.
+#[must_use]
+pub fn pdf(lang: Lang, demo: &Demo) -> Vec<u8> {
+    // PDF measures up from the bottom of the page.
+    let flip = |y: f32| HEIGHT - y;
+
+    let mut content = Vec::new();
+    for mark in layout(lang, demo) {
+        match mark {
+            Mark::Text { x, y, size, text, color } => {
+                write!(content, "BT /F1 {size} Tf {} rg {x} {} Td ", pdf_color(color), flip(y)).unwrap();
+                content.extend(pdf_string(&text));
+                writeln!(content, " Tj ET").unwrap();
+            }
+            Mark::Rect { x, y, width, height, color } => {
+                writeln!(content, "{} rg {x} {} {width} {height} re f", pdf_color(color), flip(y + height)).unwrap();
+            }
+            Mark::Line { from, to, width, color } => {
+                writeln!(content, "{} RG {width} w {} {} m {} {} l S", pdf_color(color), from.0, flip(from.1), to.0, flip(to.1)).unwrap();
+            }
+        }
+    }
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {WIDTH} {HEIGHT}] /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>"
+        )
+        .into_bytes(),
+    ];
+    let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+    stream.extend(content);
+    stream.extend(b"\nendstream");
+    objects.push(stream);
+    objects.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec());
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        writeln!(pdf, "{} 0 obj", i + 1).unwrap();
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).unwrap();
+    for offset in offsets {
+        writeln!(pdf, "{offset:010} 00000 n ").unwrap();
+    }
+    write!(pdf, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n", objects.len() + 1).unwrap();
+    pdf
+}
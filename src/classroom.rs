@@ -0,0 +1,126 @@
+use crate::demographic::{Demo, Race};
+
+use serde::{Deserialize, Serialize};
+
+/// One person in a [Roster], with their own [Demo].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Participant {
+    pub name: String,
+    pub demo: Demo,
+}
+
+/// Several participants sharing one device, drawing in lock-step.
+///
+/// Everyone gets their race, then everyone gets their starting quintile, then everyone draws
+/// generation 1 and so on, taking turns in the order they were entered.
+///
+/// # Example
+/// ```
+/// use vocar::Roster;
+///
+/// let mut roster = Roster::default();
+/// roster.start(&["Ada", "Bo"]);
+///
+/// assert_eq!(roster.current().unwrap().name, "Ada");
+/// roster.draw_current();
+/// assert_eq!(roster.current().unwrap().name, "Bo");
+///
+/// while !roster.is_finished() {
+///     roster.draw_current();
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Roster {
+    pub participants: Vec<Participant>,
+    /// Names being typed in by the facilitator, one per line.
+    pub draft: String,
+}
+
+impl Roster {
+    /// Number of draws each participant makes: race, starting quintile and every generation.
+    pub const STAGES: usize = 2 + Demo::GENERATIONS;
+
+    /// Replace the participants with fresh ones named `names`. Blank names are skipped.
+    pub fn start(&mut self, names: &[&str]) {
+        self.participants = names
+            .iter()
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .map(|name| Participant { name: name.to_string(), demo: Demo::new() })
+            .collect();
+    }
+
+    /// [Roster::start] with the names in [Roster::draft].
+    pub fn start_from_draft(&mut self) {
+        let draft = std::mem::take(&mut self.draft);
+        self.start(&draft.lines().collect::<Vec<_>>());
+        self.draft = draft;
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.participants.is_empty()
+    }
+
+    /// How many draws `demo` has had.
+    #[must_use]
+    pub fn draws(demo: &Demo) -> usize {
+        demo.draws()
+    }
+
+    /// The draw everyone is on, counting from 0 for the race.
+    #[must_use]
+    pub fn stage(&self) -> usize {
+        self.participants
+            .iter()
+            .map(|p| Self::draws(&p.demo))
+            .min()
+            .unwrap_or(Self::STAGES)
+    }
+
+    /// The generation being drawn, or `None` while races and starting quintiles are handed out.
+    #[must_use]
+    pub fn generation(&self) -> Option<usize> {
+        self.stage().checked_sub(1).filter(|&g| (1..=Demo::GENERATIONS).contains(&g))
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        !self.is_empty() && self.stage() >= Self::STAGES
+    }
+
+    /// Whose turn it is to draw.
+    #[must_use]
+    pub fn current(&self) -> Option<&Participant> {
+        let stage = self.stage();
+        self.participants
+            .iter()
+            .find(|p| stage < Self::STAGES && Self::draws(&p.demo) == stage)
+    }
+
+    /// Make the current participant's next draw.
+    pub fn draw_current(&mut self) {
+        let stage = self.stage();
+        if let Some(participant) = self
+            .participants
+            .iter_mut()
+            .find(|p| stage < Self::STAGES && Self::draws(&p.demo) == stage)
+        {
+            participant.demo.draw_next();
+        }
+    }
+
+    /// Make the next draw for everyone who hasn't had it yet.
+    pub fn draw_round(&mut self) {
+        let stage = self.stage();
+        while self.current().is_some() && self.stage() == stage {
+            self.draw_current();
+        }
+    }
+
+    /// Everyone assigned `race`, in roster order.
+    pub fn by_race(&self, race: Race) -> impl Iterator<Item = &Participant> {
+        self.participants.iter().filter(move |p| p.demo.race == Some(race))
+    }
+}
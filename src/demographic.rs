@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display, sync::OnceLock};
 
 use {
     Class::{Incarcerated, Quintile},
@@ -8,15 +8,16 @@ use {
 use rand::{
     distributions::{
         Distribution,
-        Standard,
-        WeightedIndex
+        Standard
     },
-    random, Rng, thread_rng
+    random, Rng, RngCore
 };
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+use tracing::{debug, instrument};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Class {
     Quintile(i8),
     Incarcerated,
@@ -38,6 +39,30 @@ impl Display for Class {
     }
 }
 
+impl Class {
+    /// The [Class] a random number `u` in `0..1` lands on, after the running total of `weights`:
+    /// relative chances of each quintile, then of incarceration.
+    ///
+    /// Looking a single number up this way means a replay under other weights makes the same draw
+    /// (see [Demo::as_race]).
+    #[must_use]
+    pub fn pick(weights: &[f64; 6], u: f64) -> Class {
+        let mut left = u * weights.iter().sum::<f64>();
+        let index = weights
+            .iter()
+            .position(|weight| {
+                left -= weight;
+                left < 0.0
+            })
+            .unwrap_or(weights.len() - 1);
+
+        match index {
+            5 => Incarcerated,
+            x => Quintile(x as i8 + 1),
+        }
+    }
+}
+
 impl Distribution<Class> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Class {
         match rng.gen_range(1..=5) {
@@ -50,7 +75,7 @@ impl Distribution<Class> for Standard {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum Race{
     Black,
     White,
@@ -65,6 +90,17 @@ impl Display for Race {
     }
 }
 
+impl Race {
+    /// The [Race] this one isn't.
+    #[must_use]
+    pub fn other(self) -> Race {
+        match self {
+            Race::Black => Race::White,
+            Race::White => Race::Black,
+        }
+    }
+}
+
 impl Distribution<Race> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Race {
         match rng.gen_range(0..=1) {
@@ -74,6 +110,163 @@ impl Distribution<Race> for Standard {
     }
 }
 
+/// When a generation lives, which decides the chances it is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Era {
+    /// The 1930s, long before the rise of mass incarceration.
+    Thirties,
+    /// The 1950s, when imprisonment was still rare.
+    Fifties,
+    /// The 1970s, as imprisonment started to climb.
+    Seventies,
+    /// The 1990s, during the steepest rise of mass incarceration.
+    Nineties,
+    /// The 2010s, at the height of mass incarceration.
+    Tens,
+}
+
+impl Display for Era {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Era::Thirties => write!(f, "1930s"),
+            Era::Fifties => write!(f, "1950s"),
+            Era::Seventies => write!(f, "1970s"),
+            Era::Nineties => write!(f, "1990s"),
+            Era::Tens => write!(f, "2010s"),
+        }
+    }
+}
+
+impl Era {
+    /// Every [Era], oldest first.
+    pub const ALL: [Era; 5] = [Era::Thirties, Era::Fifties, Era::Seventies, Era::Nineties, Era::Tens];
+
+    /// The [Era] of `generation` in [Eras::builtin].
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::{Demo, Era};
+    ///
+    /// assert_eq!(Era::of_generation(1), Era::Thirties);
+    /// assert_eq!(Era::of_generation(Demo::GENERATIONS), Era::Tens);
+    /// ```
+    #[must_use]
+    pub fn of_generation(generation: usize) -> Era {
+        Eras::builtin().of_generation(generation)
+    }
+}
+
+/// Relative chances of moving from each quintile to each quintile, then to incarceration, for
+/// both races.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Weights {
+    pub black: [[f64; 6]; 5],
+    pub white: [[f64; 6]; 5],
+}
+
+/// Which [Era] each generation lives in, and the chances of the eras that have their own.
+///
+/// # Example
+/// ```
+/// use vocar::{Demo, Era, Eras, Race};
+///
+/// let eras = Eras::from_ron("(generations: [Fifties, Tens], tables: {})").unwrap();
+/// assert_eq!(eras.of_generation(1), Era::Fifties);
+/// assert_eq!(eras.of_generation(Demo::GENERATIONS), Era::Tens);
+/// assert_eq!(eras.weights(Race::Black, Era::Fifties), eras.weights(Race::Black, Era::Tens));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Eras {
+    /// The [Era] of the 1st generation drawn, then of each one after. Generations past the end
+    /// keep the last one.
+    pub generations: Vec<Era>,
+    /// The chances of every [Era] that has its own. The others are drawn with the baseline.
+    pub tables: HashMap<Era, Weights>,
+}
+
+impl Eras {
+    /// The eras shipped in `assets/eras.ron`.
+    #[must_use]
+    pub fn builtin() -> &'static Eras {
+        static BUILTIN: OnceLock<Eras> = OnceLock::new();
+        BUILTIN.get_or_init(|| Eras::from_ron(include_str!("../assets/eras.ron")).expect("Failed To Parse Eras"))
+    }
+
+    /// Eras written like `assets/eras.ron`.
+    ///
+    /// # Errors
+    /// Fails if `source` isn't a list of eras.
+    pub fn from_ron(source: &str) -> Result<Eras, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    /// The [Era] of `generation`, counting the first one drawn after the starting [Class] as 1.
+    /// Without any eras listed, every generation is in the 2010s, like the baseline.
+    #[must_use]
+    pub fn of_generation(&self, generation: usize) -> Era {
+        let i = generation.saturating_sub(1).min(self.generations.len().saturating_sub(1));
+        self.generations.get(i).copied().unwrap_or(Era::Tens)
+    }
+
+    /// Whether `era` has chances of its own, rather than the baseline.
+    #[must_use]
+    pub fn has_table(&self, era: Era) -> bool {
+        self.tables.contains_key(&era)
+    }
+
+    /// The chances for `race` in `era`: its own table if it has one, or else the baseline.
+    #[must_use]
+    pub fn weights(&self, race: Race, era: Era) -> &[[f64; 6]; 5] {
+        match (self.tables.get(&era), race) {
+            (Some(weights), Black) => &weights.black,
+            (Some(weights), White) => &weights.white,
+            (None, Black) => &Demo::BLACK_WEIGHTS,
+            (None, White) => &Demo::WHITE_WEIGHTS,
+        }
+    }
+}
+
+/// The numbers behind one draw of a [Demo]: SplitMix64, started `draw` stretches of 2^32 numbers
+/// along the sequence of `seed`. Every draw can be made again on its own, as [Demo::as_race] does,
+/// without setting up a whole generator for each one.
+struct DrawRng(u64);
+
+impl DrawRng {
+    const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    fn new(seed: u64, draw: usize) -> Self {
+        DrawRng(Self::mix(seed).wrapping_add((draw as u64).wrapping_mul(Self::GAMMA) << 32))
+    }
+
+    fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl RngCore for DrawRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(Self::GAMMA);
+        Self::mix(self.0)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
 /// Create a demographic from a [Class], history [Vec<Class>] & a [Race].
 ///
 /// # Example
@@ -94,6 +287,9 @@ pub struct Demo {
     pub history: Vec<Class>,
     /// Holds [Race] which controls the likelihoods of transition to any [Class].
     pub race: Option<Race>,
+    /// Every draw is made from this, so the same seed always makes the same journey.
+    #[serde(default)]
+    pub seed: u64,
 }
 
 impl Display for Demo {
@@ -102,16 +298,16 @@ impl Display for Demo {
     /// # Example
     /// ```
     /// use vocar::{
-    ///     Demo
+    ///     Class,
+    ///     Demo,
+    ///     Race
     /// };
-    /// 
-    /// use rand::random();
-    /// 
-    /// let rand_class_black = Demo { random(), Vec::<Class>::new(), Race::Black };
-    ///
-    /// println!("{rand_class_black}");
-    /// 
-    /// // [Output]: "Black: 3rd Quintile"
+    ///
+    /// let mut black = Demo::new();
+    /// black.race = Some(Race::Black);
+    /// black.class_n = Some(Class::Quintile(3));
+    ///
+    /// assert_eq!(black.to_string(), "Black: 3rd Quintile");
     /// ```
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // write!(f, "{}: {}", self.race, self.class_n);
@@ -134,9 +330,38 @@ impl Display for Demo {
 }
 
 impl Demo {
+    /// Number of generations drawn after the starting [Class] in a full journey.
+    pub const GENERATIONS: usize = 5;
+
+    /// Bumped whenever the weights or the rules change, so old journey codes aren't misread.
+    pub const MODEL_VERSION: u8 = 4;
+
     #[must_use]
     pub fn new() -> Self {
-        Demo { class_zero: None, class_n: None, class_five: None, history: Vec::<Class>::new(), race: None }
+        Self::with_seed(random())
+    }
+
+    /// A [Demo] whose draws all come from `seed`.
+    #[must_use]
+    pub fn with_seed(seed: u64) -> Self {
+        Demo { class_zero: None, class_n: None, class_five: None, history: Vec::<Class>::new(), race: None, seed }
+    }
+
+    /// How many draws have been made, counting the race and the starting [Class].
+    #[must_use]
+    pub fn draws(&self) -> usize {
+        usize::from(self.race.is_some()) + usize::from(self.class_zero.is_some()) + self.history.len()
+    }
+
+    /// The generator for the next draw. Each draw gets its own stretch of the seed's numbers.
+    fn rng(&self) -> DrawRng {
+        DrawRng::new(self.seed, self.draws())
+    }
+
+    /// Whether every generation of the journey has been drawn.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.history.len() >= Self::GENERATIONS
     }
 
     const BLACK_WEIGHTS: [[f64; 6]; 5] = [
@@ -155,81 +380,218 @@ impl Demo {
         [2.97, 0.99, 1.98, 28.75, 64.45, 0.85],
     ];
 
-    /// Change `Demo.class` containing `Class` variant `Incarcerated` to last `Class` in `Demo.2`.
-    /// `Demo.2` is a `Vec<Class>`.
+    /// Relative chances of moving from each quintile to each quintile, then to incarceration, for
+    /// `race` in `era`, from [Eras::builtin]. Eras without a table of their own use the baseline,
+    /// which is from the 2010s.
+    #[must_use]
+    pub fn weights(race: Race, era: Era) -> &'static [[f64; 6]; 5] {
+        Eras::builtin().weights(race, era)
+    }
+
+    /// The chance of each quintile, then of incarceration, in the next generation's draw. `None`
+    /// when that isn't left to chance: before the starting [Class], and on release from prison.
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::{Class, Demo, Race};
+    ///
+    /// let mut demo = Demo::with_seed(1);
+    /// assert_eq!(demo.chances(), None);
+    ///
+    /// demo.race = Some(Race::White);
+    /// demo.class_n = Some(Class::Quintile(5));
+    /// let chances = demo.chances().unwrap();
+    /// assert!((chances.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    /// assert!(chances[4] > chances[0]);
+    /// ```
+    #[must_use]
+    pub fn chances(&self) -> Option<[f64; 6]> {
+        let (Some(race), Some(Quintile(x))) = (self.race, self.class_n) else {
+            return None;
+        };
+        let era = Era::of_generation(self.history.len() + 1);
+        let weights = Self::weights(race, era)[(x - 1) as usize];
+        let total: f64 = weights.iter().sum();
+        Some(weights.map(|weight| weight / total))
+    }
+
+    /// The [Era] of every generation drawn so far.
+    #[must_use]
+    pub fn eras(&self) -> Vec<Era> {
+        (1..=self.history.len()).map(Era::of_generation).collect()
+    }
+
+    /// How many quintiles below their previous one someone of `race` lands after incarceration.
+    #[must_use]
+    pub fn reentry_penalty(race: Race) -> i8 {
+        match race {
+            Black => 2,
+            White => 1,
+        }
+    }
+
+    /// Release an `Incarcerated` [Demo] below the [Class] they had before, by [Demo::reentry_penalty].
+    ///
+    /// The quintile released into is the generation's own entry in `history`, so `history[k]` is
+    /// always generation `k + 1` and the last entry is always `class_n`. Releasing used to push the
+    /// `Incarcerated` entry a second time and keep the quintile only in `class_n`, which counted
+    /// incarceration twice and lost where the family landed.
     fn leave_incarceration(&mut self) {
-        let previous_class = self.history[self.history.len() - 2];
+        let (race, before, released) = self.release();
+        debug!(%race, %before, to = %released, "released from incarceration");
 
-        match previous_class {
-            Quintile(mut x) => {
-                if self.race == Some(Black) {
-                    x -= 2;
-                } else {
-                    x -= 1;
-                }
+        self.history.push(released);
+        self.class_n = Some(released);
+    }
 
-                let x = x.clamp(1, 5);
+    /// The [Race], the [Class] before incarceration and the [Class] released into, for
+    /// [Demo::leave_incarceration].
+    fn release(&self) -> (Race, Class, Class) {
+        // The starting class came before the first generation, which isn't kept in `history`.
+        let previous_class = match self.history.len() {
+            0 | 1 => self.class_zero.unwrap(),
+            n => self.history[n - 2],
+        };
 
-                self.history.push(self.class_n.unwrap());
-                self.class_n = Some(Quintile(x));
+        match previous_class {
+            Quintile(x) => {
+                let race = self.race.unwrap();
+                (race, previous_class, Quintile((x - Self::reentry_penalty(race)).clamp(1, 5)))
             }
             Incarcerated => panic!("Err:[{self}] Previous State is Incarceration"),
         }
     }
 
-    /// Use Weighted Index Distribution to get a random new Class from the current [Class] and [Race].
-    fn new_class(&mut self) {
-        if self.race.is_none() {
-            self.race = random();
-            self.new_class();
-        } else {
-            
-            let weights = match self.class_n.unwrap() {
-                Quintile(x) => match self.race.unwrap() {
-                    Black => {
-                        let x: usize = (x - 1).try_into().unwrap();
-                        Self::BLACK_WEIGHTS[x]
-                    }
-                    White => {
-                        let x: usize = (x - 1).try_into().unwrap();
-                        Self::WHITE_WEIGHTS[x]
-                    }
-                },
-                Incarcerated => {
-                    panic!("Err:[{self} Incarcerated Demos Should Never Reach This Function")
-                }
-            };
-
-            let items = [
-                (Quintile(1), weights[0]),
-                (Quintile(2), weights[1]),
-                (Quintile(3), weights[2]),
-                (Quintile(4), weights[3]),
-                (Quintile(5), weights[4]),
-                (Incarcerated, weights[5]),
-            ];
-
-            let dist = WeightedIndex::new(items.iter().map(|item| item.1)).unwrap();
+    /// The [Era] of the generation being drawn, and the row of [Demo::weights] it is drawn by.
+    fn row(&self, race: Race) -> (Era, &'static [f64; 6]) {
+        let era = Era::of_generation(self.history.len() + 1);
+        match self.class_n.unwrap() {
+            Quintile(x) => {
+                let x: usize = (x - 1).try_into().unwrap();
+                (era, &Self::weights(race, era)[x])
+            }
+            Incarcerated => {
+                panic!("Err:[{self} Incarcerated Demos Should Never Reach This Function")
+            }
+        }
+    }
 
-            let new_class: Class = items[dist.sample(&mut thread_rng())].0;
+    /// Draw a new [Class] from the current [Class] and [Race], weighted by [Demo::weights] in the
+    /// [Era] of the generation being drawn.
+    fn new_class(&mut self) {
+        if let Some(race) = self.race {
+            let (era, weights) = self.row(race);
+            let new_class = Class::pick(weights, self.rng().gen());
+            debug!(%race, %era, from = %self.class_n.unwrap(), ?weights, to = %new_class, "drew a generation");
 
             self.history.push(new_class);
             self.class_n = Some(new_class);
+        } else {
+            let race = self.rng().gen();
+            debug!(%race, "drew the race");
+            self.race = Some(race);
+            self.new_class();
         }
     }
 
+    /// Draw the [Race], the first draw of a journey. A journey drawn before, even one only
+    /// [reset](Demo::reset), is forgotten along with its race.
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::Demo;
+    ///
+    /// let mut demo = Demo::with_seed(3);
+    /// demo.draw_race();
+    /// demo.draw_next();
+    /// demo.reset();
+    ///
+    /// demo.draw_race();
+    /// assert!(demo.race.is_some() && demo.class_zero.is_none());
+    /// assert_eq!(demo.draws(), 1);
+    /// ```
+    pub fn draw_race(&mut self) {
+        if self.race.is_some() {
+            self.reset();
+            self.race = None;
+        }
+        self.draw_next();
+    }
+
+    /// Make the journey's next draw: the [Race], then the starting [Class], then each generation.
+    #[instrument(level = "debug", skip_all, fields(seed = self.seed, draw = self.draws()))]
+    pub fn draw_next(&mut self) {
+        if self.race.is_none() {
+            let race = self.rng().gen();
+            debug!(%race, "drew the race");
+            self.race = Some(race);
+        } else if self.class_zero.is_none() {
+            let class: Class = self.rng().gen();
+            debug!(%class, "drew the starting class");
+            self.class_zero = Some(class);
+            self.class_n = self.class_zero;
+        } else {
+            self.next_gen();
+        }
+    }
+
+    /// Draw the generation after the current [Class].
+    ///
+    /// # Panics
+    /// Without a current [Class], as on a new journey or after [Demo::reset]. [Demo::draw_next]
+    /// draws whatever is missing first.
+    #[instrument(level = "debug", skip_all, fields(seed = self.seed, generation = self.history.len() + 1))]
     pub fn next_gen(&mut self) {
-        dbg!(&self.class_n.unwrap());
         match self.class_n.unwrap() {
             Quintile(_) => self.new_class(),
             Incarcerated => self.leave_incarceration(),
         }
     }
 
+    /// This journey replayed as if its [Race] were `race`: the same starting [Class] and the same
+    /// random numbers every generation, so any difference comes from the weights and the re-entry penalty.
+    /// Replaying doesn't report any draws.
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::Demo;
+    ///
+    /// let mut demo = Demo::with_seed(3);
+    /// while !demo.is_finished() {
+    ///     demo.draw_next();
+    /// }
+    ///
+    /// let race = demo.race.unwrap();
+    /// assert_eq!(demo.as_race(race).history, demo.history);
+    /// assert_eq!(demo.as_race(race.other()).class_zero, demo.class_zero);
+    /// ```
+    #[must_use]
+    #[instrument(level = "debug", skip(self), fields(seed = self.seed))]
+    pub fn as_race(&self, race: Race) -> Demo {
+        let mut replay = Demo::with_seed(self.seed);
+        replay.race = Some(race);
+        replay.class_zero = self.class_zero;
+        replay.class_n = self.class_zero;
+
+        // Nothing is reported: the journey already was, as it was drawn.
+        while replay.history.len() < self.history.len() {
+            let next = match replay.class_n {
+                Some(Quintile(_)) => Class::pick(replay.row(race).1, replay.rng().gen()),
+                Some(Incarcerated) => replay.release().2,
+                None => break,
+            };
+            replay.history.push(next);
+            replay.class_n = Some(next);
+        }
+        replay
+    }
+
     pub fn reset(&mut self) {
+        debug!(seed = self.seed, draws = self.draws(), "reset the journey");
         self.class_zero = None;
         self.class_n = None;
         self.class_five = None;
         self.history.clear();
+        self.seed = random();
     }
 }
\ No newline at end of file
@@ -0,0 +1,52 @@
+//! Handing files to the participant: saved to disk on desktop, downloaded by the browser on the web.
+
+use std::io;
+
+/// Save `bytes` as a file called `name`, returning where it went.
+///
+/// On desktop the file goes in the Downloads folder if there is one, the current folder otherwise.
+///
+/// # Errors
+/// Fails if the file can't be written or the browser refuses the download.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(name: &str, _mime: &str, bytes: &[u8]) -> io::Result<String> {
+    let downloads = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| std::path::PathBuf::from(home).join("Downloads"))
+        .filter(|folder| folder.is_dir());
+    let path = match downloads {
+        Some(folder) => folder.join(name),
+        None => std::env::current_dir()?.join(name),
+    };
+
+    std::fs::write(&path, bytes)?;
+    Ok(path.display().to_string())
+}
+
+/// Save `bytes` as a file called `name`, returning where it went.
+///
+/// # Errors
+/// Fails if the file can't be written or the browser refuses the download.
+#[cfg(target_arch = "wasm32")]
+pub fn save(name: &str, mime: &str, bytes: &[u8]) -> io::Result<String> {
+    use wasm_bindgen::JsCast;
+
+    let js = |error: wasm_bindgen::JsValue| io::Error::new(io::ErrorKind::Other, format!("{error:?}"));
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let mut options = web_sys::BlobPropertyBag::new();
+    options.type_(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options).map_err(js)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Err: No Document To Download From."))?;
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a").map_err(js)?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(js)?;
+    Ok(name.to_string())
+}
@@ -0,0 +1,171 @@
+use std::fmt::Display;
+
+use crate::{app::Screen, classroom::Roster, demographic::Demo};
+
+/// What the participant asked for on the current [Screen].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Pressed the [Screen]'s main button, after any draw it makes has been applied.
+    Next,
+    /// Start over from [Screen::Start].
+    Restart,
+    /// Set up several participants to share the device.
+    Classroom,
+    /// Try out policy interventions on the model.
+    Scenarios,
+    /// Grow whole family trees instead of a single journey.
+    Lineage,
+}
+
+/// Everything the flow between [Screen]s depends on.
+#[derive(Debug, Clone, Copy)]
+pub struct State<'a> {
+    /// The single participant's journey.
+    pub demo: &'a Demo,
+    /// The participants of a classroom session.
+    pub roster: &'a Roster,
+    /// A journey someone else shared, opened from its code.
+    pub shared: Option<&'a Demo>,
+    /// Whether the reflection surveys are asked before and after a journey.
+    pub surveys: bool,
+}
+
+/// Why a [Screen] can't be shown for a [State].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowError {
+    /// The [Screen] needs a race but none was drawn.
+    MissingRace(Screen),
+    /// The [Screen] needs a current class but none was drawn.
+    MissingClass(Screen),
+    /// The [Screen] is only for journeys with every generation drawn.
+    Unfinished(Screen),
+    /// The [Screen] needs participants but none were entered.
+    EmptyRoster(Screen),
+    /// The [Screen] is only for classrooms where everyone has drawn every generation.
+    RosterUnfinished(Screen),
+    /// The [Screen] shows a shared journey but none was opened.
+    NothingShared(Screen),
+}
+
+impl Display for FlowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlowError::MissingRace(s) => write!(f, "Err: {s:?} Needs A Race But None Was Drawn."),
+            FlowError::MissingClass(s) => write!(f, "Err: {s:?} Needs A Class But None Was Drawn."),
+            FlowError::Unfinished(s) => write!(f, "Err: {s:?} Needs All {} Generations.", Demo::GENERATIONS),
+            FlowError::EmptyRoster(s) => write!(f, "Err: {s:?} Needs At Least One Participant."),
+            FlowError::RosterUnfinished(s) => write!(f, "Err: {s:?} Needs Every Participant To Finish."),
+            FlowError::NothingShared(s) => write!(f, "Err: {s:?} Needs A Shared Journey."),
+        }
+    }
+}
+
+impl std::error::Error for FlowError {}
+
+/// Check that `state` has everything `screen` needs to be drawn.
+///
+/// # Errors
+/// Returns a [FlowError] naming the first missing piece.
+pub fn check(screen: Screen, state: State<'_>) -> Result<(), FlowError> {
+    let State { demo, roster, shared, surveys: _ } = state;
+
+    let needs_race = matches!(
+        screen,
+        Screen::RaceDisplay
+            | Screen::ClassDetermination
+            | Screen::ClassDisplay
+            | Screen::FactDisplay
+            | Screen::MissedFactDisplay
+            | Screen::End
+            | Screen::PostSurvey
+    );
+    let needs_class = matches!(screen, Screen::ClassDisplay | Screen::FactDisplay);
+    let needs_finish = matches!(screen, Screen::MissedFactDisplay | Screen::End | Screen::PostSurvey);
+    let needs_roster = matches!(screen, Screen::RosterDraw | Screen::Scoreboard);
+    let needs_roster_finish = matches!(screen, Screen::Scoreboard);
+    let needs_shared = matches!(screen, Screen::SharedJourney);
+
+    if needs_race && demo.race.is_none() {
+        Err(FlowError::MissingRace(screen))
+    } else if needs_class && demo.class_n.is_none() {
+        Err(FlowError::MissingClass(screen))
+    } else if needs_finish && !demo.is_finished() {
+        Err(FlowError::Unfinished(screen))
+    } else if needs_roster && roster.is_empty() {
+        Err(FlowError::EmptyRoster(screen))
+    } else if needs_roster_finish && !roster.is_finished() {
+        Err(FlowError::RosterUnfinished(screen))
+    } else if needs_shared && shared.is_none() {
+        Err(FlowError::NothingShared(screen))
+    } else {
+        Ok(())
+    }
+}
+
+/// The [Screen] that follows `screen` when `action` is taken in `state`.
+///
+/// # Errors
+/// Returns a [FlowError] when the following [Screen] can't be drawn for `state`.
+///
+/// # Example
+/// ```
+/// use vocar::{flow::{self, Action, State}, Demo, Roster, Screen};
+///
+/// let (demo, roster) = (Demo::new(), Roster::default());
+/// let state = State { demo: &demo, roster: &roster, shared: None, surveys: false };
+///
+/// assert_eq!(flow::transition(Screen::Start, state, Action::Next), Ok(Screen::RaceDetermination));
+/// assert!(flow::transition(Screen::RaceDetermination, state, Action::Next).is_err());
+/// ```
+pub fn transition(screen: Screen, state: State<'_>, action: Action) -> Result<Screen, FlowError> {
+    let next = match action {
+        Action::Restart => Screen::Start,
+        Action::Classroom => Screen::RosterSetup,
+        Action::Scenarios => Screen::Scenarios,
+        Action::Lineage => Screen::Lineage,
+        Action::Next => match screen {
+            Screen::Start if state.surveys => Screen::PreSurvey,
+            Screen::Start | Screen::PreSurvey => Screen::RaceDetermination,
+            Screen::RaceDetermination => Screen::RaceDisplay,
+            Screen::RaceDisplay => Screen::ClassDetermination,
+            Screen::ClassDetermination => Screen::ClassDisplay,
+            Screen::ClassDisplay => Screen::FactDisplay,
+            Screen::FactDisplay => {
+                if state.demo.is_finished() {
+                    Screen::MissedFactDisplay
+                } else {
+                    Screen::ClassDetermination
+                }
+            }
+            Screen::MissedFactDisplay => Screen::End,
+            Screen::End if state.surveys => Screen::PostSurvey,
+            Screen::End | Screen::PostSurvey => Screen::Start,
+            Screen::RosterSetup => Screen::RosterDraw,
+            Screen::RosterDraw => {
+                if state.roster.is_finished() {
+                    Screen::Scoreboard
+                } else {
+                    Screen::RosterDraw
+                }
+            }
+            Screen::Scoreboard => Screen::Start,
+            Screen::SharedJourney => Screen::Start,
+            Screen::Scenarios => Screen::Start,
+            Screen::Lineage => Screen::Start,
+        },
+    };
+
+    check(next, state).map(|()| next)
+}
+
+/// `screen` if it can be drawn for `state`, [Screen::Start] otherwise.
+#[must_use]
+pub fn guard(screen: Screen, state: State<'_>) -> Screen {
+    match check(screen, state) {
+        Ok(()) => screen,
+        Err(error) => {
+            tracing::warn!(%error, "fell back to the start screen");
+            Screen::Start
+        }
+    }
+}
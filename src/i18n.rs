@@ -0,0 +1,169 @@
+use std::{collections::HashMap, fmt::Display, sync::OnceLock};
+
+use crate::demographic::{Class, Demo, Era, Race};
+
+use serde::{Deserialize, Serialize};
+
+/// A message catalog mapping keys like `"start.heading"` to translated text.
+type Catalog = HashMap<String, String>;
+
+/// Languages the Vocar can be shown in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Lang {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Lang {
+    /// Every [Lang], in the order they are offered to participants.
+    pub const ALL: [Lang; 2] = [Lang::English, Lang::Spanish];
+
+    /// The language's name, written in that language.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lang::English => "English",
+            Lang::Spanish => "Español",
+        }
+    }
+
+    fn catalog(&self) -> &'static Catalog {
+        static ENGLISH: OnceLock<Catalog> = OnceLock::new();
+        static SPANISH: OnceLock<Catalog> = OnceLock::new();
+
+        match self {
+            Lang::English => ENGLISH.get_or_init(|| parse(include_str!("../assets/locales/en.ron"))),
+            Lang::Spanish => SPANISH.get_or_init(|| parse(include_str!("../assets/locales/es.ron"))),
+        }
+    }
+
+    /// Look up `key`, falling back to English and then to the key itself.
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::Lang;
+    ///
+    /// assert_eq!(Lang::Spanish.tr("race.black"), "negra");
+    /// assert_eq!(Lang::Spanish.tr("no.such.key"), "no.such.key");
+    /// ```
+    #[must_use]
+    pub fn tr<'a>(&self, key: &'a str) -> &'a str {
+        self.catalog()
+            .get(key)
+            .or_else(|| Lang::English.catalog().get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// Look up `key` and fill in its `{name}` placeholders from `args`.
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::Lang;
+    ///
+    /// assert_eq!(Lang::English.trf("card.generation", &[("generation", &3)]), "Generation 3: ");
+    /// ```
+    #[must_use]
+    pub fn trf(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter().fold(self.tr(key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+    }
+
+    /// The ordinal form of `n` used in quintile labels, e.g. "3rd" or "3.er".
+    #[must_use]
+    pub fn ordinal(&self, n: i8) -> String {
+        match self {
+            Lang::English => match n {
+                1 => "1st".to_string(),
+                2 => "2nd".to_string(),
+                3 => "3rd".to_string(),
+                x => format!("{x}th"),
+            },
+            Lang::Spanish => match n {
+                1 | 3 => format!("{n}.er"),
+                x => format!("{x}.º"),
+            },
+        }
+    }
+
+    /// `amount` of dollars rounded to whole dollars, with thousands grouped the local way.
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::Lang;
+    ///
+    /// assert_eq!(Lang::English.money(1234567.4), "$1,234,567");
+    /// assert_eq!(Lang::Spanish.money(-950.0), "-950 US$");
+    /// ```
+    #[must_use]
+    pub fn money(&self, amount: f64) -> String {
+        let separator = match self {
+            Lang::English => ',',
+            Lang::Spanish => '.',
+        };
+        let digits = format!("{:.0}", amount.abs());
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        let sign = if amount <= -0.5 { "-" } else { "" };
+
+        self.trf("money", &[("sign", &sign), ("amount", &grouped)])
+    }
+}
+
+fn parse(source: &str) -> Catalog {
+    ron::from_str(source).expect("Failed To Parse Message Catalog")
+}
+
+/// Text for showing a value to participants in a given [Lang].
+pub trait Localize {
+    fn localize(&self, lang: Lang) -> String;
+}
+
+impl Localize for Race {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            Race::Black => lang.tr("race.black").to_string(),
+            Race::White => lang.tr("race.white").to_string(),
+        }
+    }
+}
+
+impl Localize for Class {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            Class::Quintile(x) => lang.trf("class.quintile", &[("ordinal", &lang.ordinal(*x))]),
+            Class::Incarcerated => lang.tr("class.incarcerated").to_string(),
+        }
+    }
+}
+
+impl Localize for Era {
+    fn localize(&self, lang: Lang) -> String {
+        match self {
+            Era::Thirties => lang.tr("era.thirties").to_string(),
+            Era::Fifties => lang.tr("era.fifties").to_string(),
+            Era::Seventies => lang.tr("era.seventies").to_string(),
+            Era::Nineties => lang.tr("era.nineties").to_string(),
+            Era::Tens => lang.tr("era.tens").to_string(),
+        }
+    }
+}
+
+impl Localize for Demo {
+    fn localize(&self, lang: Lang) -> String {
+        match (self.race, self.class_n) {
+            (Some(race), Some(class)) => lang.trf(
+                "demo.summary",
+                &[("race", &race.localize(lang)), ("class", &class.localize(lang))],
+            ),
+            (Some(race), None) => lang.trf("demo.unknown_class", &[("race", &race.localize(lang))]),
+            (None, _) => lang.tr("demo.empty").to_string(),
+        }
+    }
+}
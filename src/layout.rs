@@ -0,0 +1,89 @@
+use crate::a11y;
+
+use serde::{Deserialize, Serialize};
+
+/// Screens narrower than this many points stack the Vocar Card below the content.
+pub const NARROW_WIDTH: f32 = 600.0;
+
+/// Share of the available height left blank above a screen's heading.
+const TOP_SPACE: f32 = 0.22;
+
+/// How large the UI is drawn. Remembered between runs.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct View {
+    /// Multiplier on the display's own pixels per point.
+    pub zoom: f32,
+    /// Use [a11y::high_contrast_visuals] instead of the regular theme.
+    pub high_contrast: bool,
+    /// What `high_contrast` was when the visuals were last set, if they have been.
+    #[serde(skip)]
+    applied_contrast: Option<bool>,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        View { zoom: 1.0, high_contrast: false, applied_contrast: None }
+    }
+}
+
+impl View {
+    /// Storage key the [View] is saved under, apart from the rest of the app's state.
+    pub const KEY: &'static str = "vocar_view";
+    /// Zoom used for large-text projector mode.
+    pub const PROJECTOR_ZOOM: f32 = 2.0;
+
+    const MIN_ZOOM: f32 = 0.5;
+    const MAX_ZOOM: f32 = 3.0;
+
+    #[must_use]
+    pub fn is_projector(&self) -> bool {
+        self.zoom >= Self::PROJECTOR_ZOOM
+    }
+
+    /// Switch between projector and normal text size.
+    pub fn toggle_projector(&mut self) {
+        self.zoom = if self.is_projector() { 1.0 } else { Self::PROJECTOR_ZOOM };
+    }
+
+    /// Handle the zoom keyboard shortcuts and scale and theme `ctx` to match the [View].
+    pub fn apply(&mut self, ctx: &egui::Context, native_pixels_per_point: Option<f32>) {
+        use egui::gui_zoom::kb_shortcuts::{ZOOM_IN, ZOOM_OUT, ZOOM_RESET};
+
+        if ctx.input_mut(|i| i.consume_shortcut(&ZOOM_RESET)) {
+            self.zoom = 1.0;
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&ZOOM_IN)) {
+            self.zoom += 0.1;
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&ZOOM_OUT)) {
+            self.zoom -= 0.1;
+        }
+        self.zoom = ((self.zoom * 10.0).round() / 10.0).clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+
+        let pixels_per_point = native_pixels_per_point.unwrap_or(1.0) * self.zoom;
+        if (ctx.pixels_per_point() - pixels_per_point).abs() > f32::EPSILON {
+            ctx.set_pixels_per_point(pixels_per_point);
+        }
+
+        // Leave the visuals alone until high contrast is first used, so the system theme still applies.
+        match (self.applied_contrast, self.high_contrast) {
+            (Some(applied), wanted) if applied == wanted => {}
+            (None, false) => {}
+            (_, true) => ctx.set_visuals(a11y::high_contrast_visuals()),
+            (_, false) => ctx.set_visuals(egui::Visuals::default()),
+        }
+        self.applied_contrast = Some(self.high_contrast);
+    }
+}
+
+/// Whether the screen is too narrow to show panels side by side.
+#[must_use]
+pub fn is_narrow(ctx: &egui::Context) -> bool {
+    ctx.screen_rect().width() < NARROW_WIDTH
+}
+
+/// Leave room above a screen's heading in proportion to the space available.
+pub fn top_space(ui: &mut egui::Ui) {
+    ui.add_space(ui.available_height() * TOP_SPACE);
+}
@@ -1,5 +1,9 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+mod a11y;
+
+pub mod analytics;
+
 mod app;
 pub use app::{
     Screen::{
@@ -9,9 +13,69 @@ pub use app::{
     Vocar
 };
 
+pub mod card;
+
+mod classroom;
+pub use classroom::{Participant, Roster};
+
+mod download;
+
 mod demographic;
 pub use demographic::{
     Demo,
     Class::{self, *},
+    Era,
+    Eras,
+    Weights,
     Race::{self, *}
-};
\ No newline at end of file
+};
+
+pub mod flow;
+
+pub mod lineage;
+
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+pub mod server;
+
+pub mod share;
+
+pub mod stats;
+
+pub mod story;
+
+pub mod survey;
+
+mod session;
+pub use session::{Opened, Room, SessionError, Sessions, Summary};
+
+mod i18n;
+pub use i18n::{Lang, Localize};
+
+pub mod model;
+
+pub mod quiz;
+
+pub mod wealth;
+
+mod layout;
+pub use layout::View;
+
+mod nav;
+pub use nav::Navigator;
+
+/// The [tracing::Level] named by `level`: one of `error`, `warn`, `info`, `debug` or `trace`.
+/// Without one, or with a misspelt one, only `info` and up are logged.
+///
+/// # Example
+/// ```
+/// use tracing::Level;
+///
+/// assert_eq!(vocar::log_level(Some(" debug ")), Level::DEBUG);
+/// assert_eq!(vocar::log_level(Some("TRACE")), Level::TRACE);
+/// assert_eq!(vocar::log_level(Some("loud")), Level::INFO);
+/// assert_eq!(vocar::log_level(None), Level::INFO);
+/// ```
+#[must_use]
+pub fn log_level(level: Option<&str>) -> tracing::Level {
+    level.and_then(|level| level.trim().parse().ok()).unwrap_or(tracing::Level::INFO)
+}
@@ -0,0 +1,108 @@
+//! Family trees: instead of one line of descent, everyone has several children who each draw
+//! their own class.
+
+use crate::{
+    demographic::{Class, Demo, Era, Race},
+    model::{Model, Rules},
+};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+/// Someone in a family tree, with all their descendants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Person {
+    pub class: Class,
+    pub children: Vec<Person>,
+}
+
+impl Person {
+    /// Everyone `generation`s below this person, this person being generation 0.
+    #[must_use]
+    pub fn generation(&self, generation: usize) -> Vec<&Person> {
+        let mut people = vec![self];
+        for _ in 0..generation {
+            people = people.iter().flat_map(|person| &person.children).collect();
+        }
+        people
+    }
+
+    /// How many of [Person::generation] are in each quintile, then incarcerated.
+    #[must_use]
+    pub fn composition(&self, generation: usize) -> [usize; 6] {
+        let mut counts = [0; 6];
+        for person in self.generation(generation) {
+            match person.class {
+                Class::Quintile(x) => counts[(x - 1) as usize] += 1,
+                Class::Incarcerated => counts[5] += 1,
+            }
+        }
+        counts
+    }
+}
+
+/// How to grow a family tree.
+///
+/// # Example
+/// ```
+/// use vocar::{lineage::Lineage, Demo, Race};
+///
+/// let lineage = Lineage { children: 2, start: 3, seed: 1 };
+/// let family = lineage.grow(Race::White);
+///
+/// assert_eq!(family.generation(Demo::GENERATIONS).len(), 32);
+/// assert_eq!(family.composition(Demo::GENERATIONS).iter().sum::<usize>(), 32);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Lineage {
+    /// Children every person has.
+    pub children: usize,
+    /// Quintile of the first person.
+    pub start: i8,
+    /// Every family grown from the same seed gets the same random numbers.
+    pub seed: u64,
+}
+
+impl Default for Lineage {
+    fn default() -> Self {
+        Lineage { children: 2, start: 3, seed: rand::random() }
+    }
+}
+
+impl Lineage {
+    /// More children than this makes the last generation too big to show.
+    pub const MAX_CHILDREN: usize = 4;
+
+    /// A family of `race` over every generation, following the baseline [Model].
+    #[must_use]
+    #[instrument(level = "debug", skip(self), fields(children = self.children, start = self.start, seed = self.seed))]
+    pub fn grow(&self, race: Race) -> Person {
+        let model = Model::baseline();
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let start = self.start.clamp(1, 5);
+        grow(model.rules(race), Class::Quintile(start), start, 0, self.children, &mut rng)
+    }
+}
+
+/// Someone in `class` and `generation`, whose last quintile was `before`, with descendants down to
+/// the last generation of a journey.
+fn grow(rules: &Rules, class: Class, before: i8, generation: usize, children: usize, rng: &mut ChaCha8Rng) -> Person {
+    let before = match class {
+        Class::Quintile(x) => x,
+        Class::Incarcerated => before,
+    };
+    let children = if generation >= Demo::GENERATIONS { 0 } else { children };
+
+    Person {
+        class,
+        children: (0..children)
+            .map(|_| {
+                let child = rules.next(Era::of_generation(generation + 1), class, before, rng.gen());
+                grow(rules, child, before, generation + 1, children, rng)
+            })
+            .collect(),
+    }
+}
@@ -1,10 +1,29 @@
 //#![warn(clippy::all, rust_2018_idioms)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+/// Environment variable naming the most verbose level to log, e.g. `VOCAR_LOG=debug`.
+#[cfg(not(target_arch = "wasm32"))]
+const LOG_VAR: &str = "VOCAR_LOG";
+
+/// Query parameter naming the most verbose level to log, e.g. `?log=debug`.
+#[cfg(target_arch = "wasm32")]
+const LOG_QUERY: &str = "log";
+
+/// Command line flag launching the app for a facilitator, who may go back past a draw to redo it.
+#[cfg(not(target_arch = "wasm32"))]
+const FACILITATOR_FLAG: &str = "--facilitator";
+
+/// Query parameter launching the app for a facilitator, e.g. `?facilitator`. Only read by builds
+/// with the `web-facilitator` feature, since participants could type it as well.
+#[cfg(all(target_arch = "wasm32", feature = "web-facilitator"))]
+const FACILITATOR_QUERY: &str = "facilitator";
 
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
-    tracing_subscriber::fmt::init();
+    tracing_subscriber::fmt()
+        .with_max_level(vocar::log_level(std::env::var(LOG_VAR).ok().as_deref()))
+        .init();
+    let facilitator = std::env::args().any(|arg| arg == FACILITATOR_FLAG);
 
     let icon = image::open("./assets/icons/apple-touch-icon.png")
         .expect("Failed To Open Icon Path")
@@ -28,7 +47,11 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Vocar",
         options,
-        Box::new(|cc| Box::new(vocar::Vocar::new(cc))),
+        Box::new(move |cc| {
+            let mut vocar = vocar::Vocar::new(cc);
+            vocar.set_facilitator(facilitator);
+            Box::new(vocar)
+        }),
     )
 }
 
@@ -36,16 +59,30 @@ fn main() -> eframe::Result<()> {
 fn main() {
     console_error_panic_hook::set_once();
 
-    tracing_wasm::set_as_global_default();
+    let params = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok());
+    let level = params.as_ref().and_then(|params| params.get(LOG_QUERY));
+    #[cfg(feature = "web-facilitator")]
+    let facilitator = params.is_some_and(|params| params.has(FACILITATOR_QUERY));
+    #[cfg(not(feature = "web-facilitator"))]
+    let facilitator = false;
+    tracing_wasm::set_as_global_default_with_config(
+        tracing_wasm::WASMLayerConfigBuilder::new().set_max_level(vocar::log_level(level.as_deref())).build()
+    );
 
     let web_options = eframe::WebOptions::default();
 
     wasm_bindgen_futures::spawn_local(
-        async {
+        async move {
             eframe::start_web(
                 "vocar_web",
                 web_options,
-                Box::new(|cc| Box::new(vocar::Vocar::new(cc)))
+                Box::new(move |cc| {
+                    let mut vocar = vocar::Vocar::new(cc);
+                    vocar.set_facilitator(facilitator);
+                    Box::new(vocar)
+                })
             ).await.expect("Failed To Start Eframe");
         }
     );
@@ -0,0 +1,360 @@
+//! The chances journeys are drawn with, and policy interventions that change them.
+//!
+//! Journeys themselves always use the baseline. A [Model] with interventions applied is only ever
+//! analysed, to compare outcomes before and after.
+
+use crate::demographic::{Class, Demo, Era, Race};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+/// Everything that decides one [Race]'s chances.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    /// Per [Era], relative chances of moving from each quintile to each quintile, then to incarceration.
+    pub weights: [[[f64; 6]; 5]; Era::ALL.len()],
+    /// How many quintiles below their previous one someone lands after incarceration.
+    pub reentry_penalty: i8,
+}
+
+impl Rules {
+    /// The chances in `era`.
+    #[must_use]
+    pub fn weights(&self, era: Era) -> &[[f64; 6]; 5] {
+        &self.weights[era as usize]
+    }
+
+    /// Where someone in quintile `from` goes next in `era`, for a random number `u` in `0..1`.
+    /// `before` is the quintile they had before, used if `from` is incarceration.
+    #[must_use]
+    pub fn next(&self, era: Era, from: Class, before: i8, u: f64) -> Class {
+        match from {
+            Class::Quintile(x) => Class::pick(&self.weights(era)[(x - 1) as usize], u),
+            Class::Incarcerated => Class::Quintile((before - self.reentry_penalty).clamp(1, 5)),
+        }
+    }
+
+    /// These rules with every row prebuilt for drawing many journeys quickly.
+    #[must_use]
+    pub fn sampler(&self) -> Sampler {
+        Sampler {
+            tables: self.weights.map(|era| era.map(|row| Alias::new(&row))),
+            reentry_penalty: self.reentry_penalty,
+        }
+    }
+}
+
+/// A row of chances as an alias table (Vose's method): one of its six columns is picked evenly,
+/// then kept or swapped for its alias, so every draw takes the same few steps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Alias {
+    /// Chance of keeping each column once picked.
+    keep: [f64; 6],
+    /// What each column is swapped for otherwise.
+    alias: [u8; 6],
+}
+
+impl Alias {
+    fn new(weights: &[f64; 6]) -> Self {
+        let total: f64 = weights.iter().sum();
+        let mut scaled = weights.map(|weight| weight * 6.0 / total);
+        let mut table = Alias { keep: [1.0; 6], alias: [0, 1, 2, 3, 4, 5] };
+
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..6).partition(|&i| scaled[i] < 1.0);
+        // Whatever is left over once either runs out only differs from 1 by rounding, so it keeps 1.
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+            table.keep[less] = scaled[less];
+            table.alias[less] = more as u8;
+            scaled[more] -= 1.0 - scaled[less];
+            if scaled[more] < 1.0 {
+                small.push(more);
+            } else {
+                large.push(more);
+            }
+        }
+        table
+    }
+
+    /// The column a random number `u` in `0..1` lands on.
+    fn pick(&self, u: f64) -> usize {
+        let spread = u * 6.0;
+        let column = (spread as usize).min(5);
+        if spread - (column as f64) < self.keep[column] {
+            column
+        } else {
+            usize::from(self.alias[column])
+        }
+    }
+}
+
+/// [Rules] ready for drawing many journeys, see [Rules::sampler].
+///
+/// Draws come out with the same chances as [Rules::next], but a random number doesn't land on
+/// the same [Class] under other weights, so journeys that should be compared draw by draw, like
+/// [crate::Demo::as_race] and [Model::simulate_paired], use [Rules::next] instead.
+///
+/// # Example
+/// ```
+/// use vocar::{model::Model, Class, Era, Race};
+///
+/// let sampler = Model::baseline().rules(Race::White).sampler();
+///
+/// assert!(matches!(sampler.next(Era::Tens, Class::Quintile(3), 3, 0.5), Class::Quintile(_)));
+/// assert_eq!(sampler.next(Era::Tens, Class::Incarcerated, 4, 0.5), Class::Quintile(3));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sampler {
+    tables: [[Alias; 5]; Era::ALL.len()],
+    reentry_penalty: i8,
+}
+
+impl Sampler {
+    /// Where someone in `from` goes next in `era`, for a random number `u` in `0..1`, like [Rules::next].
+    #[must_use]
+    pub fn next(&self, era: Era, from: Class, before: i8, u: f64) -> Class {
+        match from {
+            Class::Quintile(x) => match self.tables[era as usize][(x - 1) as usize].pick(u) {
+                5 => Class::Incarcerated,
+                column => Class::Quintile(column as i8 + 1),
+            },
+            Class::Incarcerated => Class::Quintile((before - self.reentry_penalty).clamp(1, 5)),
+        }
+    }
+}
+
+/// The [Rules] for every [Race].
+///
+/// # Example
+/// ```
+/// use vocar::{model::{Intervention, Model}, Race};
+///
+/// let after = Model::baseline().with(&[Intervention::HalveIncarceration]);
+/// let before = Model::baseline().distribution(Race::Black, 5);
+///
+/// assert!(after.distribution(Race::Black, 5)[5] < before[5]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Model {
+    pub black: Rules,
+    pub white: Rules,
+}
+
+impl Model {
+    /// The chances every journey is drawn with.
+    #[must_use]
+    pub fn baseline() -> Self {
+        let rules = |race| Rules {
+            weights: Era::ALL.map(|era| *Demo::weights(race, era)),
+            reentry_penalty: Demo::reentry_penalty(race),
+        };
+        Model { black: rules(Race::Black), white: rules(Race::White) }
+    }
+
+    #[must_use]
+    pub fn rules(&self, race: Race) -> &Rules {
+        match race {
+            Race::Black => &self.black,
+            Race::White => &self.white,
+        }
+    }
+
+    /// This model with every one of `interventions` applied, in order.
+    #[must_use]
+    pub fn with(mut self, interventions: &[Intervention]) -> Self {
+        for intervention in interventions {
+            intervention.apply(&mut self);
+        }
+        self
+    }
+
+    /// The exact chance of being in each quintile, then incarcerated, after `generations` draws
+    /// from a random starting quintile, each in the [Era] of its generation.
+    #[must_use]
+    pub fn distribution(&self, race: Race, generations: usize) -> [f64; 6] {
+        let rules = self.rules(race);
+        // Where people are free, then where people incarcerated from each quintile are.
+        let mut free = [0.2; 5];
+        let mut held = [0.0; 5];
+
+        for generation in 1..=generations {
+            let mut next_free = [0.0; 5];
+            let mut next_held = [0.0; 5];
+
+            for (from, weights) in rules.weights(Era::of_generation(generation)).iter().enumerate() {
+                let total: f64 = weights.iter().sum();
+                for (to, weight) in weights[..5].iter().enumerate() {
+                    next_free[to] += free[from] * weight / total;
+                }
+                next_held[from] += free[from] * weights[5] / total;
+
+                let released = (from as i8 + 1 - rules.reentry_penalty).clamp(1, 5);
+                next_free[(released - 1) as usize] += held[from];
+            }
+
+            (free, held) = (next_free, next_held);
+        }
+
+        [free[0], free[1], free[2], free[3], free[4], held.iter().sum()]
+    }
+
+    /// The share of `runs` random journeys in each quintile, then incarcerated, after `generations`.
+    /// The same `seed` always gives the same result.
+    #[must_use]
+    #[instrument(level = "debug", skip(self))]
+    pub fn simulate(&self, race: Race, generations: usize, runs: usize, seed: u64) -> [f64; 6] {
+        let sampler = self.rules(race).sampler();
+        let shares = simulate(generations, runs, seed, |era, class, before, u| sampler.next(era, class, before, u));
+        debug!(?shares, "simulated");
+        shares
+    }
+
+    /// Like [Model::simulate], but every draw looks its random number up with [Class::pick], the
+    /// way [Demo::as_race] replays a journey. Two models simulated from the same `seed` then make
+    /// the same draw wherever their chances agree, so the difference between them comes from the
+    /// chances rather than luck. Slower than [Model::simulate], which uses a [Sampler].
+    #[must_use]
+    #[instrument(level = "debug", skip(self))]
+    pub fn simulate_paired(&self, race: Race, generations: usize, runs: usize, seed: u64) -> [f64; 6] {
+        let rules = self.rules(race);
+        let shares = simulate(generations, runs, seed, |era, class, before, u| rules.next(era, class, before, u));
+        debug!(?shares, "simulated paired");
+        shares
+    }
+}
+
+/// The share of `runs` random journeys in each quintile, then incarcerated, after `generations`
+/// drawn with `next`, which is given each draw's [Era], [Class], quintile before and random number.
+fn simulate(generations: usize, runs: usize, seed: u64, next: impl Fn(Era, Class, i8, f64) -> Class) -> [f64; 6] {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut counts = [0usize; 6];
+
+    for _ in 0..runs {
+        let mut class = Class::Quintile(rng.gen_range(1..=5));
+        let mut before = 1;
+        for generation in 1..=generations {
+            if let Class::Quintile(x) = class {
+                before = x;
+            }
+            class = next(Era::of_generation(generation), class, before, rng.gen());
+        }
+        match class {
+            Class::Quintile(x) => counts[(x - 1) as usize] += 1,
+            Class::Incarcerated => counts[5] += 1,
+        }
+    }
+
+    counts.map(|count| count as f64 / runs.max(1) as f64)
+}
+
+/// A named change to the [Model], for asking "what would change this?".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Intervention {
+    /// Every chance of incarceration is halved.
+    HalveIncarceration,
+    /// Everyone gets the smallest re-entry penalty.
+    EqualReentryPenalty,
+    /// Everyone in the 1st quintile gets the chances of the group most likely to move up from it.
+    EqualBottomMobility,
+    /// Baby bonds: in every quintile, 5% of the chance to land there moves up to the next one.
+    BabyBonds,
+}
+
+impl Intervention {
+    pub const ALL: [Intervention; 4] = [
+        Intervention::HalveIncarceration,
+        Intervention::EqualReentryPenalty,
+        Intervention::EqualBottomMobility,
+        Intervention::BabyBonds,
+    ];
+
+    /// Share of each quintile's chance that [Intervention::BabyBonds] moves up.
+    const BABY_BOND_SHIFT: f64 = 0.05;
+
+    /// Name of the intervention in the message catalogs, also used for its description.
+    #[must_use]
+    pub fn key(&self) -> &'static str {
+        match self {
+            Intervention::HalveIncarceration => "intervention.halve_incarceration",
+            Intervention::EqualReentryPenalty => "intervention.equal_reentry_penalty",
+            Intervention::EqualBottomMobility => "intervention.equal_bottom_mobility",
+            Intervention::BabyBonds => "intervention.baby_bonds",
+        }
+    }
+
+    pub fn apply(&self, model: &mut Model) {
+        match self {
+            Intervention::HalveIncarceration => {
+                for rules in [&mut model.black, &mut model.white] {
+                    for row in rules.weights.iter_mut().flatten() {
+                        row[5] /= 2.0;
+                    }
+                }
+            }
+            Intervention::EqualReentryPenalty => {
+                let penalty = model.black.reentry_penalty.min(model.white.reentry_penalty);
+                model.black.reentry_penalty = penalty;
+                model.white.reentry_penalty = penalty;
+            }
+            Intervention::EqualBottomMobility => {
+                let upward = |row: &[f64; 6]| row[1..5].iter().sum::<f64>() / row.iter().sum::<f64>();
+                for (black, white) in model.black.weights.iter_mut().zip(&mut model.white.weights) {
+                    let best = if upward(&black[0]) >= upward(&white[0]) { black[0] } else { white[0] };
+                    black[0] = best;
+                    white[0] = best;
+                }
+            }
+            Intervention::BabyBonds => {
+                for rules in [&mut model.black, &mut model.white] {
+                    for row in rules.weights.iter_mut().flatten() {
+                        // From the top down, so nothing moves up twice.
+                        for to in (0..4).rev() {
+                            let shift = row[to] * Self::BABY_BOND_SHIFT;
+                            row[to] -= shift;
+                            row[to + 1] += shift;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How to work out the outcomes of a [Model].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Follow the chances exactly.
+    Exact,
+    /// Draw `runs` random journeys per group from `seed`. Before and after use the same random
+    /// numbers, so differences come from the interventions rather than luck.
+    MonteCarlo { runs: usize, seed: u64 },
+}
+
+/// One group's outcomes without and with some interventions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub race: Race,
+    /// Share in each quintile, then incarcerated, under the baseline.
+    pub before: [f64; 6],
+    /// Share in each quintile, then incarcerated, with the interventions.
+    pub after: [f64; 6],
+}
+
+/// Per [Race], the outcomes of a full journey before and after `interventions`.
+#[must_use]
+pub fn compare(interventions: &[Intervention], method: Method) -> Vec<Comparison> {
+    let before = Model::baseline();
+    let after = before.clone().with(interventions);
+    let outcome = |model: &Model, race| match method {
+        Method::Exact => model.distribution(race, Demo::GENERATIONS),
+        Method::MonteCarlo { runs, seed } => model.simulate_paired(race, Demo::GENERATIONS, runs, seed),
+    };
+
+    [Race::Black, Race::White]
+        .into_iter()
+        .map(|race| Comparison { race, before: outcome(&before, race), after: outcome(&after, race) })
+        .collect()
+}
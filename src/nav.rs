@@ -0,0 +1,79 @@
+use crate::{app::Screen, demographic::Demo};
+
+use serde::{Deserialize, Serialize};
+
+/// A [Screen] that was left, together with the [Demo] as it was on that [Screen].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Visit {
+    screen: Screen,
+    demo: Demo,
+}
+
+/// Stack of previously visited [Screen]s used for "Back" navigation.
+///
+/// Going back restores the [Demo] to how it was on the earlier [Screen], which undoes any draw
+/// made since. Returning to a [Screen] that rolls dice is therefore only allowed in facilitator mode.
+///
+/// # Example
+/// ```
+/// use vocar::{Demo, Navigator, Screen};
+///
+/// let mut nav = Navigator::default();
+/// let mut screen = Screen::RaceDetermination;
+/// let mut demo = Demo::new();
+///
+/// nav.push(Screen::Start, &demo);
+/// assert!(nav.back(&mut screen, &mut demo));
+/// assert_eq!(screen, Screen::Start);
+/// ```
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Navigator {
+    /// Previously visited [Screen]s, most recent last.
+    stack: Vec<Visit>,
+    /// Allows going back to [Screen]s that roll dice, so their draw can be made again with a new seed.
+    /// Only set by launching the app in facilitator mode, never restored from saved state.
+    #[serde(skip)]
+    pub facilitator: bool,
+}
+
+impl Navigator {
+    /// Remember that `screen` was left while `demo` looked like it does now.
+    pub fn push(&mut self, screen: Screen, demo: &Demo) {
+        self.stack.push(Visit { screen, demo: demo.clone() });
+    }
+
+    /// Whether [Navigator::back] would do anything.
+    #[must_use]
+    pub fn can_back(&self) -> bool {
+        match self.stack.last() {
+            Some(visit) => self.facilitator || !visit.screen.rolls_dice(),
+            None => false,
+        }
+    }
+
+    /// Return to the previous [Screen], restoring the [Demo] it was left with.
+    /// Returns `false` and changes nothing if going back is not allowed.
+    pub fn back(&mut self, screen: &mut Screen, demo: &mut Demo) -> bool {
+        if !self.can_back() {
+            return false;
+        }
+
+        let Some(visit) = self.stack.pop() else {
+            return false;
+        };
+
+        *screen = visit.screen;
+        *demo = visit.demo;
+        if screen.rolls_dice() {
+            // Otherwise the same seed would make the same draw again.
+            demo.seed = rand::random();
+        }
+        true
+    }
+
+    /// Forget every visited [Screen], e.g. when a new journey begins.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+}
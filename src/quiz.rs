@@ -0,0 +1,152 @@
+//! Prediction mode: before each draw the participant guesses what comes next, and after it they
+//! see the chances it was really drawn with.
+
+use crate::demographic::{Class, Demo};
+
+use serde::{Deserialize, Serialize};
+
+/// Where `class` is in a row of chances: the quintiles, then incarceration.
+fn index(class: Class) -> usize {
+    match class {
+        Class::Quintile(x) => (x - 1) as usize,
+        Class::Incarcerated => 5,
+    }
+}
+
+/// What the participant expects of the next draw. Either part can be left out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Guess {
+    /// The [Class] they think comes next.
+    pub class: Option<Class>,
+    /// Their estimate of the chance of incarceration, from 0 to 1.
+    pub incarceration: Option<f64>,
+}
+
+/// A [Guess] next to the draw it was about.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Prediction {
+    /// The generation drawn, counting the first one after the starting [Class] as 1.
+    pub generation: usize,
+    pub guess: Guess,
+    /// The real chance of each quintile, then of incarceration.
+    pub chances: [f64; 6],
+    pub drawn: Class,
+}
+
+impl Prediction {
+    /// The real chance of `class`.
+    #[must_use]
+    pub fn chance(&self, class: Class) -> f64 {
+        self.chances[index(class)]
+    }
+
+    /// Whether the guessed [Class] was the likeliest one, or as likely as it.
+    #[must_use]
+    pub fn picked_likeliest(&self) -> Option<bool> {
+        let likeliest = self.chances.iter().copied().fold(0.0, f64::max);
+        self.guess.class.map(|class| self.chance(class) >= likeliest)
+    }
+
+    /// How far the estimate of the chance of incarceration was from the real one, from 0 to 1.
+    #[must_use]
+    pub fn incarceration_error(&self) -> Option<f64> {
+        self.guess.incarceration.map(|estimate| (estimate - self.chance(Class::Incarcerated)).abs())
+    }
+}
+
+/// How a journey's predictions did, summed up.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Score {
+    /// Draws a [Class] was guessed for.
+    pub guessed: usize,
+    /// Of those, how often the likeliest [Class] was picked.
+    pub likeliest: usize,
+    /// Of those, how often the guess was drawn.
+    pub came_true: usize,
+    /// Draws the chance of incarceration was estimated for.
+    pub estimated: usize,
+    /// The average [Prediction::incarceration_error] of those.
+    pub incarceration_error: Option<f64>,
+}
+
+/// Whether prediction mode is on, the [Guess] being made and every [Prediction] of the journey.
+///
+/// # Example
+/// ```
+/// use vocar::{quiz::{Guess, Quiz}, Class, Demo};
+///
+/// let mut demo = Demo::with_seed(2);
+/// demo.draw_next();
+/// demo.draw_next();
+///
+/// let mut quiz = Quiz { enabled: true, ..Quiz::default() };
+/// quiz.guess = Guess { class: demo.class_n, incarceration: Some(0.5) };
+/// let before = demo.clone();
+/// demo.draw_next();
+/// quiz.record(&before, &demo);
+///
+/// let prediction = quiz.prediction(1).unwrap();
+/// assert_eq!(Some(prediction.drawn), demo.class_n);
+/// assert_eq!(quiz.score().guessed, 1);
+/// ```
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Quiz {
+    /// Guesses are only asked for if this is set.
+    pub enabled: bool,
+    /// The guess for the next draw, as far as it has been made.
+    pub guess: Guess,
+    /// The journey's predictions, by generation.
+    pub predictions: Vec<Prediction>,
+}
+
+impl Quiz {
+    /// Keep the [Guess] made for the draw that turned `before` into `after`, and clear it for the
+    /// next one. Draws that weren't left to chance, or made outside prediction mode, are skipped.
+    pub fn record(&mut self, before: &Demo, after: &Demo) {
+        let guess = std::mem::take(&mut self.guess);
+        let (Some(chances), Some(&drawn)) = (before.chances(), after.history.last()) else {
+            return;
+        };
+        if !self.enabled || after.history.len() != before.history.len() + 1 {
+            return;
+        }
+
+        let generation = after.history.len();
+        // A draw made again after going back replaces the first one.
+        self.predictions.retain(|prediction| prediction.generation != generation);
+        self.predictions.push(Prediction { generation, guess, chances, drawn });
+    }
+
+    /// The [Prediction] made for `generation`, if one was.
+    #[must_use]
+    pub fn prediction(&self, generation: usize) -> Option<&Prediction> {
+        self.predictions.iter().find(|prediction| prediction.generation == generation)
+    }
+
+    /// Forget the journey's predictions, e.g. when a new journey begins.
+    pub fn clear(&mut self) {
+        self.guess = Guess::default();
+        self.predictions.clear();
+    }
+
+    /// How the journey's predictions did.
+    #[must_use]
+    pub fn score(&self) -> Score {
+        let mut score = Score::default();
+        let mut error = 0.0;
+        for prediction in &self.predictions {
+            if let (Some(class), Some(likeliest)) = (prediction.guess.class, prediction.picked_likeliest()) {
+                score.guessed += 1;
+                score.likeliest += usize::from(likeliest);
+                score.came_true += usize::from(class == prediction.drawn);
+            }
+            if let Some(off) = prediction.incarceration_error() {
+                score.estimated += 1;
+                error += off;
+            }
+        }
+        score.incarceration_error = (score.estimated > 0).then(|| error / score.estimated as f64);
+        score
+    }
+}
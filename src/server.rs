@@ -0,0 +1,405 @@
+//! A small HTTP server hosting shared [Sessions] on the local network.
+//!
+//! Everything runs on the standard library, so it works on a single laptop with no internet. It is
+//! only built with the `server` feature, e.g. `cargo run --features server --bin vocar-server`.
+//! Request bodies over 4 KiB are refused with `413`, and connections past
+//! [Server::MAX_CONNECTIONS] or rooms past [Sessions::MAX_ROOMS] with `503`.
+//!
+//! | Request                                  | Response                                  |
+//! |------------------------------------------|-------------------------------------------|
+//! | `GET /`                                  | The participant and facilitator web page. |
+//! | `POST /rooms`                            | A new room's code and facilitator key.    |
+//! | `GET /rooms/{code}`                      | The room's [Summary].                     |
+//! | `GET /rooms/{code}/events`               | A server-sent event stream of [Summary]s. |
+//! | `POST /rooms/{code}/participants`        | Join with the body as your name.          |
+//! | `GET /rooms/{code}/participants/{id}`    | Your journey, by the id you joined with.  |
+//! | `POST /rooms/{code}/advance?key={key}`   | Everyone's next draw.                     |
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::session::{SessionError, Sessions, Summary};
+
+use serde::Serialize;
+
+/// How often an idle event stream is pinged, so dropped connections get noticed.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// How long a client may take to send its request, or to take in what is sent to it, before the
+/// connection is given up on.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a client turned away for [Server::MAX_CONNECTIONS] may take to send its request.
+const REFUSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The longest request line and headers read, in bytes.
+const MAX_HEAD: u64 = 8 * 1024;
+
+/// The longest body accepted, in bytes. Names and other payloads are far shorter.
+const MAX_BODY: usize = 4 * 1024;
+
+/// How much of a refused body is read and thrown away, so the client isn't cut off before it reads
+/// why. Anything longer is cut off.
+const MAX_DISCARD: u64 = 1024 * 1024;
+
+const PAGE: &str = include_str!("../assets/server/index.html");
+
+/// [Sessions] shared between connections, with a signal for every change.
+#[derive(Default)]
+struct Shared {
+    sessions: Mutex<Sessions>,
+    changed: Condvar,
+    /// Connections being handled, each on its own thread.
+    connections: AtomicUsize,
+}
+
+/// A running session server.
+///
+/// # Example
+/// ```
+/// use vocar::server::{Client, Server};
+///
+/// let server = Server::spawn("127.0.0.1:0").unwrap();
+/// let client = Client::new(server.addr());
+///
+/// let (status, body) = client.request("POST", "/rooms", "").unwrap();
+/// assert_eq!(status, 201);
+/// let opened: vocar::Opened = serde_json::from_str(&body).unwrap();
+///
+/// let path = format!("/rooms/{}/participants", opened.code);
+/// assert_eq!(client.request("POST", &path, "Ada").unwrap().0, 201);
+///
+/// let path = format!("/rooms/{}/advance?key={}", opened.code, opened.key);
+/// assert_eq!(client.request("POST", &path, "").unwrap().0, 200);
+///
+/// let latest = &client.events(&opened.code, 1).unwrap()[0];
+/// assert_eq!((latest.participants, latest.stage), (1, 1));
+/// ```
+pub struct Server {
+    addr: SocketAddr,
+}
+
+impl Server {
+    /// The most connections handled at once, event streams included. A classroom needs one or two
+    /// per participant.
+    pub const MAX_CONNECTIONS: usize = 256;
+
+    /// Start serving on `addr` in the background. Use port `0` to pick any free port.
+    ///
+    /// # Errors
+    /// Fails if `addr` can't be listened on.
+    pub fn spawn(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        thread::spawn(move || serve(&listener));
+        Ok(Server { addr })
+    }
+
+    /// Serve on `addr` until the process ends.
+    ///
+    /// # Errors
+    /// Fails if `addr` can't be listened on.
+    pub fn run(addr: impl ToSocketAddrs) -> io::Result<()> {
+        serve(&TcpListener::bind(addr)?);
+        Ok(())
+    }
+
+    /// The address the server is listening on.
+    #[must_use]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+fn serve(listener: &TcpListener) {
+    let shared = Arc::new(Shared::default());
+
+    // Connections past the limit are turned away one at a time on a thread of their own, and
+    // dropped unanswered if even that falls behind.
+    let (refuse, refused) = mpsc::sync_channel::<TcpStream>(Server::MAX_CONNECTIONS);
+    thread::spawn(move || {
+        for stream in refused {
+            let _ = refuse_connection(&stream);
+        }
+    });
+
+    for stream in listener.incoming().flatten() {
+        if shared.connections.fetch_add(1, Ordering::SeqCst) >= Server::MAX_CONNECTIONS {
+            shared.connections.fetch_sub(1, Ordering::SeqCst);
+            let _ = refuse.try_send(stream);
+            continue;
+        }
+
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            // A client hanging up halfway, or stalling, is nothing the server needs to act on.
+            let _ = stream
+                .set_read_timeout(Some(TIMEOUT))
+                .and_then(|()| stream.set_write_timeout(Some(TIMEOUT)))
+                .and_then(|()| handle(stream, &shared));
+            shared.connections.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+/// Answer `503` once the request is read, so the client isn't cut off before it reads why.
+fn refuse_connection(stream: &TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(REFUSE_TIMEOUT))?;
+    stream.set_write_timeout(Some(REFUSE_TIMEOUT))?;
+    read_request(stream)?;
+    respond(stream, 503, "text/plain; charset=utf-8", "Err: Too Many Connections.")
+}
+
+/// The parts of an HTTP request the server looks at.
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    /// The `Content-Length` the client sent. The body is only read if it is at most [MAX_BODY].
+    length: usize,
+    body: String,
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream.take(MAX_HEAD + MAX_BODY as u64));
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (path, query) = (path.to_string(), query.to_string());
+
+    let mut length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; if length > MAX_BODY { 0 } else { length }];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+
+    Ok(Request { method, path, query, length, body })
+}
+
+fn respond(mut stream: &TcpStream, status: u16, content_type: &str, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        503 => "Service Unavailable",
+        _ => "Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// A response's status, content type and body, worked out before anything is written.
+type Reply = (u16, &'static str, String);
+
+fn json(status: u16, value: &impl Serialize) -> io::Result<Reply> {
+    let body = serde_json::to_string(value).map_err(io::Error::other)?;
+    Ok((status, "application/json", body))
+}
+
+fn text(status: u16, body: impl ToString) -> Reply {
+    (status, "text/plain; charset=utf-8", body.to_string())
+}
+
+fn error_reply(error: SessionError) -> Reply {
+    let status = match error {
+        SessionError::NoSuchRoom | SessionError::NoSuchParticipant => 404,
+        SessionError::WrongKey => 403,
+        SessionError::AlreadyStarted | SessionError::RoomFull => 409,
+        SessionError::BlankName => 400,
+        SessionError::TooManyRooms => 503,
+    };
+    text(status, error)
+}
+
+fn handle(stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    let request = read_request(&stream)?;
+    if request.length > MAX_BODY {
+        respond(&stream, 413, "text/plain; charset=utf-8", "Err: Request Body Too Large.")?;
+        stream.shutdown(Shutdown::Write)?;
+        io::copy(&mut (&stream).take(MAX_DISCARD), &mut io::sink())?;
+        return Ok(());
+    }
+
+    let segments: Vec<&str> = request.path.split('/').filter(|s| !s.is_empty()).collect();
+    if let ("GET", ["rooms", code, "events"]) = (request.method.as_str(), segments.as_slice()) {
+        return stream_events(stream, shared, code);
+    }
+
+    // The lock is let go of before writing, so a slow client never holds up the other rooms.
+    let (status, content_type, body) = answer(&request, &segments, shared)?;
+    respond(&stream, status, content_type, &body)
+}
+
+/// What to send back for `request`, worked out with the [Sessions] locked.
+fn answer(request: &Request, segments: &[&str], shared: &Shared) -> io::Result<Reply> {
+    let key = request
+        .query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("key="))
+        .unwrap_or_default();
+
+    let mut sessions = shared.sessions.lock().unwrap();
+    let result = match (request.method.as_str(), segments) {
+        ("GET", []) => return Ok((200, "text/html; charset=utf-8", PAGE.to_string())),
+        ("POST", ["rooms"]) => match sessions.open(&mut rand::thread_rng()) {
+            Ok(opened) => return json(201, &opened),
+            Err(error) => Err(error),
+        },
+        ("GET", ["rooms", code]) => sessions.summary(code).map(|summary| (200, summary)),
+        ("POST", ["rooms", code, "participants"]) => match sessions.join(code, &request.body, &mut rand::thread_rng()) {
+            Ok(id) => {
+                shared.changed.notify_all();
+                return json(201, &serde_json::json!({ "id": id }));
+            }
+            Err(error) => Err(error),
+        },
+        ("GET", ["rooms", code, "participants", id]) => match sessions.participant(code, id) {
+            Ok(participant) => return json(200, participant),
+            Err(error) => Err(error),
+        },
+        ("POST", ["rooms", code, "advance"]) => sessions.advance(code, key).and_then(|()| {
+            shared.changed.notify_all();
+            sessions.summary(code).map(|summary| (200, summary))
+        }),
+        _ => return Ok(text(404, "Err: Not Found.")),
+    };
+
+    match result {
+        Ok((status, summary)) => json(status, &summary),
+        Err(error) => Ok(error_reply(error)),
+    }
+}
+
+/// Send the room's [Summary] now and after every change, until the client goes away. Each
+/// [Summary] is taken while the [Sessions] are locked and written once they are let go of.
+fn stream_events(mut stream: TcpStream, shared: &Shared, code: &str) -> io::Result<()> {
+    let first = shared.sessions.lock().unwrap().summary(code);
+    let mut last = match first {
+        Ok(first) => first,
+        Err(error) => {
+            let (status, content_type, body) = error_reply(error);
+            return respond(&stream, status, content_type, &body);
+        }
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n"
+    )?;
+    write!(stream, "data: {}\n\n", serde_json::to_string(&last).map_err(io::Error::other)?)?;
+    stream.flush()?;
+
+    loop {
+        let next = {
+            let sessions = shared.sessions.lock().unwrap();
+            let (sessions, timeout) = shared
+                .changed
+                .wait_timeout_while(sessions, KEEP_ALIVE, |sessions| {
+                    sessions.summary(code).is_ok_and(|summary| summary.version == last.version)
+                })
+                .unwrap();
+            if timeout.timed_out() {
+                None
+            } else {
+                Some(sessions.summary(code).map_err(io::Error::other)?)
+            }
+        };
+
+        match next {
+            Some(summary) => {
+                last = summary;
+                write!(stream, "data: {}\n\n", serde_json::to_string(&last).map_err(io::Error::other)?)?;
+            }
+            None => write!(stream, ": ping\n\n")?,
+        }
+        stream.flush()?;
+    }
+}
+
+/// A minimal blocking HTTP client for talking to a [Server], e.g. from tests.
+pub struct Client {
+    addr: SocketAddr,
+}
+
+impl Client {
+    #[must_use]
+    pub fn new(addr: SocketAddr) -> Self {
+        Client { addr }
+    }
+
+    /// Send a request and return the response's status code and body.
+    ///
+    /// # Errors
+    /// Fails if the server can't be reached or sends back something that isn't HTTP.
+    pub fn request(&self, method: &str, path: &str, body: &str) -> io::Result<(u16, String)> {
+        let mut stream = TcpStream::connect(self.addr)?;
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.addr,
+            body.len()
+        )?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+
+        let status = response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Err: Not An HTTP Response."))?;
+        let body = response.split_once("\r\n\r\n").map(|(_, body)| body.to_string()).unwrap_or_default();
+
+        Ok((status, body))
+    }
+
+    /// Open a room's event stream and return the first `count` [Summary]s sent on it.
+    ///
+    /// # Errors
+    /// Fails if the server can't be reached or the stream ends early.
+    pub fn events(&self, code: &str, count: usize) -> io::Result<Vec<Summary>> {
+        let mut stream = TcpStream::connect(self.addr)?;
+        write!(stream, "GET /rooms/{code}/events HTTP/1.1\r\nHost: {}\r\n\r\n", self.addr)?;
+
+        let mut summaries = Vec::with_capacity(count);
+        for line in BufReader::new(stream).lines() {
+            if let Some(data) = line?.strip_prefix("data: ") {
+                summaries.push(serde_json::from_str(data).map_err(io::Error::other)?);
+                if summaries.len() == count {
+                    break;
+                }
+            }
+        }
+        Ok(summaries)
+    }
+}
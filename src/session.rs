@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use crate::{
+    classroom::{Participant, Roster},
+    demographic::Demo,
+    stats::{self, Group},
+};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Letters used in room codes. Look-alikes such as `I`/`L` and `O`/`Q` are left out.
+const CODE_LETTERS: &[u8] = b"ABCDEFGHJKMNPRSTUVWXYZ";
+const CODE_LENGTH: usize = 4;
+
+/// Why a [Sessions] request was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionError {
+    /// No room has the given code.
+    NoSuchRoom,
+    /// No participant has the given id.
+    NoSuchParticipant,
+    /// The facilitator key doesn't match the room's.
+    WrongKey,
+    /// Participants can only join before the first draw.
+    AlreadyStarted,
+    /// Names can't be blank.
+    BlankName,
+    /// [Sessions::MAX_ROOMS] are already open.
+    TooManyRooms,
+    /// The room already has [Sessions::MAX_PARTICIPANTS].
+    RoomFull,
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::NoSuchRoom => write!(f, "Err: No Room Has That Code."),
+            SessionError::NoSuchParticipant => write!(f, "Err: No Participant Has That Id."),
+            SessionError::WrongKey => write!(f, "Err: Wrong Facilitator Key."),
+            SessionError::AlreadyStarted => write!(f, "Err: The Session Has Already Started."),
+            SessionError::BlankName => write!(f, "Err: Names Can't Be Blank."),
+            SessionError::TooManyRooms => write!(f, "Err: Too Many Rooms Are Open."),
+            SessionError::RoomFull => write!(f, "Err: The Room Is Full."),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+/// What the facilitator gets back when opening a room.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Opened {
+    /// Short code participants type in to join.
+    pub code: String,
+    /// Secret the facilitator needs to advance the room.
+    pub key: String,
+}
+
+/// A shared session: one [Roster] drawn in lock-step when the facilitator says so.
+#[derive(Debug, Clone, Default)]
+pub struct Room {
+    key: String,
+    pub roster: Roster,
+    /// The secret id of each participant in the [Roster], in the same order.
+    ids: Vec<String>,
+    /// Bumped on every change, so watchers know when to send an update.
+    pub version: u64,
+}
+
+/// Where everyone in a [Room] stands. What the facilitator dashboard shows.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Summary {
+    pub code: String,
+    pub version: u64,
+    pub participants: usize,
+    /// Draws made so far, counting the race and the starting quintile.
+    pub stage: usize,
+    pub finished: bool,
+    /// Everyone counted up by race, next to what's expected.
+    pub groups: Vec<Group>,
+}
+
+/// Every open [Room], by code.
+#[derive(Debug, Default)]
+pub struct Sessions {
+    rooms: HashMap<String, Room>,
+}
+
+impl Sessions {
+    /// The most rooms open at once. Rooms are kept until the server stops.
+    pub const MAX_ROOMS: usize = 100;
+
+    /// The most participants in one [Room].
+    pub const MAX_PARTICIPANTS: usize = 200;
+
+    /// Open a new [Room] under a code no other room is using.
+    ///
+    /// # Errors
+    /// [SessionError::TooManyRooms] if [Sessions::MAX_ROOMS] are already open.
+    pub fn open<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<Opened, SessionError> {
+        if self.rooms.len() >= Self::MAX_ROOMS {
+            return Err(SessionError::TooManyRooms);
+        }
+        let code = loop {
+            let code: String = (0..CODE_LENGTH)
+                .map(|_| char::from(CODE_LETTERS[rng.gen_range(0..CODE_LETTERS.len())]))
+                .collect();
+            if !self.rooms.contains_key(&code) {
+                break code;
+            }
+        };
+        let key = format!("{:016x}", rng.gen::<u64>());
+
+        self.rooms.insert(code.clone(), Room { key: key.clone(), ..Room::default() });
+        Ok(Opened { code, key })
+    }
+
+    /// The [Room] with `code`, ignoring case.
+    ///
+    /// # Errors
+    /// [SessionError::NoSuchRoom] if there isn't one.
+    pub fn room(&self, code: &str) -> Result<&Room, SessionError> {
+        self.rooms.get(&code.to_ascii_uppercase()).ok_or(SessionError::NoSuchRoom)
+    }
+
+    fn room_mut(&mut self, code: &str) -> Result<&mut Room, SessionError> {
+        self.rooms.get_mut(&code.to_ascii_uppercase()).ok_or(SessionError::NoSuchRoom)
+    }
+
+    /// Add a participant called `name`, returning their id. Ids are random, so only the
+    /// participant who joined can look themselves up.
+    ///
+    /// # Errors
+    /// Fails if the room doesn't exist, is full, has started drawing, or `name` is blank.
+    pub fn join<R: Rng + ?Sized>(&mut self, code: &str, name: &str, rng: &mut R) -> Result<String, SessionError> {
+        let room = self.room_mut(code)?;
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(SessionError::BlankName);
+        }
+        if room.roster.participants.iter().any(|p| Roster::draws(&p.demo) > 0) {
+            return Err(SessionError::AlreadyStarted);
+        }
+        if room.roster.participants.len() >= Self::MAX_PARTICIPANTS {
+            return Err(SessionError::RoomFull);
+        }
+
+        let id = format!("{:016x}", rng.gen::<u64>());
+        room.roster.participants.push(Participant { name: name.to_string(), demo: Demo::new() });
+        room.ids.push(id.clone());
+        room.version += 1;
+        Ok(id)
+    }
+
+    /// The participant with `id`.
+    ///
+    /// # Errors
+    /// Fails if the room or participant doesn't exist.
+    pub fn participant(&self, code: &str, id: &str) -> Result<&Participant, SessionError> {
+        let room = self.room(code)?;
+        let index = room.ids.iter().position(|known| known == id).ok_or(SessionError::NoSuchParticipant)?;
+        Ok(&room.roster.participants[index])
+    }
+
+    /// Make everyone's next draw, if `key` is the room's facilitator key.
+    ///
+    /// # Errors
+    /// Fails if the room doesn't exist or `key` is wrong.
+    pub fn advance(&mut self, code: &str, key: &str) -> Result<(), SessionError> {
+        let room = self.room_mut(code)?;
+        if room.key != key {
+            return Err(SessionError::WrongKey);
+        }
+
+        let stage = room.roster.stage();
+        room.roster.draw_round();
+        if room.roster.stage() != stage {
+            room.version += 1;
+        }
+        Ok(())
+    }
+
+    /// Where everyone in the room currently stands.
+    ///
+    /// # Errors
+    /// [SessionError::NoSuchRoom] if the room doesn't exist.
+    pub fn summary(&self, code: &str) -> Result<Summary, SessionError> {
+        let room = self.room(code)?;
+
+        Ok(Summary {
+            code: code.to_ascii_uppercase(),
+            version: room.version,
+            participants: room.roster.participants.len(),
+            stage: if room.roster.is_empty() { 0 } else { room.roster.stage() },
+            finished: room.roster.is_finished(),
+            groups: stats::groups(room.roster.participants.iter().map(|p| &p.demo)),
+        })
+    }
+}
@@ -0,0 +1,165 @@
+//! Short, URL-safe codes for finished journeys, so they can be shown or collected without a server.
+//!
+//! A code packs 14 bytes, written out in unpadded URL-safe base64:
+//!
+//! | Bytes  | Holds                                                              |
+//! |--------|--------------------------------------------------------------------|
+//! | 0      | [Demo::MODEL_VERSION].                                             |
+//! | 1      | The [Race]: `0` for Black, `1` for White.                          |
+//! | 2..10  | The [Demo::seed], little-endian.                                   |
+//! | 10..12 | The starting [Class] and every generation, as base 6 digits.       |
+//! | 12..14 | A Fletcher-16 checksum of the bytes before it.                     |
+
+use std::fmt::Display;
+
+use crate::demographic::{Class, Demo, Race};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+/// The URL query parameter a journey code is passed in, as in `?journey={code}`.
+pub const QUERY: &str = "journey";
+
+const LENGTH: usize = 14;
+
+/// Why a journey code couldn't be made or read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeError {
+    /// Only journeys with every generation drawn get a code.
+    Unfinished,
+    /// The code isn't one this app makes, e.g. it was cut short when copied.
+    Malformed,
+    /// The code was changed after it was made.
+    Checksum,
+    /// The code was made with a different [Demo::MODEL_VERSION].
+    Version(u8),
+}
+
+impl Display for CodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeError::Unfinished => write!(f, "Err: Only Finished Journeys Have A Code."),
+            CodeError::Malformed => write!(f, "Err: That Isn't A Journey Code."),
+            CodeError::Checksum => write!(f, "Err: The Journey Code Has A Typo."),
+            CodeError::Version(v) => write!(f, "Err: The Journey Code Is For Model Version {v}."),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+fn digit(class: Class) -> u16 {
+    match class {
+        Class::Quintile(x) => (x - 1) as u16,
+        Class::Incarcerated => 5,
+    }
+}
+
+fn class(digit: u16) -> Class {
+    match digit {
+        5 => Class::Incarcerated,
+        x => Class::Quintile(x as i8 + 1),
+    }
+}
+
+fn checksum(bytes: &[u8]) -> [u8; 2] {
+    let (mut low, mut high) = (0u16, 0u16);
+    for &byte in bytes {
+        low = (low + u16::from(byte)) % 255;
+        high = (high + low) % 255;
+    }
+    [high as u8, low as u8]
+}
+
+/// The code for a finished `demo`.
+///
+/// # Errors
+/// [CodeError::Unfinished] unless `demo` has a race and every generation.
+///
+/// # Example
+/// ```
+/// use vocar::{share, Demo};
+///
+/// let mut demo = Demo::with_seed(7);
+/// while !demo.is_finished() {
+///     demo.draw_next();
+/// }
+///
+/// let code = share::encode(&demo).unwrap();
+/// let decoded = share::decode(&code).unwrap();
+/// assert_eq!((decoded.race, decoded.seed, decoded.history.len()), (demo.race, 7, 5));
+/// ```
+pub fn encode(demo: &Demo) -> Result<String, CodeError> {
+    let (Some(race), Some(class_zero)) = (demo.race, demo.class_zero) else {
+        return Err(CodeError::Unfinished);
+    };
+    if demo.history.len() != Demo::GENERATIONS {
+        return Err(CodeError::Unfinished);
+    }
+
+    let classes = std::iter::once(&class_zero)
+        .chain(&demo.history)
+        .fold(0u16, |packed, &class| packed * 6 + digit(class));
+
+    let mut bytes = Vec::with_capacity(LENGTH);
+    bytes.push(Demo::MODEL_VERSION);
+    bytes.push(match race {
+        Race::Black => 0,
+        Race::White => 1,
+    });
+    bytes.extend(demo.seed.to_le_bytes());
+    bytes.extend(classes.to_le_bytes());
+    bytes.extend(checksum(&bytes));
+
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// The journey a code was made from.
+///
+/// # Errors
+/// Fails if the code is cut short, mistyped, or was made by another model version.
+pub fn decode(code: &str) -> Result<Demo, CodeError> {
+    let bytes = URL_SAFE_NO_PAD.decode(code.trim()).map_err(|_| CodeError::Malformed)?;
+    if bytes.len() != LENGTH {
+        return Err(CodeError::Malformed);
+    }
+    if checksum(&bytes[..LENGTH - 2]) != bytes[LENGTH - 2..] {
+        return Err(CodeError::Checksum);
+    }
+    if bytes[0] != Demo::MODEL_VERSION {
+        return Err(CodeError::Version(bytes[0]));
+    }
+
+    let race = match bytes[1] {
+        0 => Race::Black,
+        1 => Race::White,
+        _ => return Err(CodeError::Malformed),
+    };
+    let seed = u64::from_le_bytes(bytes[2..10].try_into().unwrap());
+    let mut packed = u16::from_le_bytes([bytes[10], bytes[11]]);
+
+    let mut classes = [Class::Incarcerated; 1 + Demo::GENERATIONS];
+    for slot in classes.iter_mut().rev() {
+        *slot = class(packed % 6);
+        packed /= 6;
+    }
+    let starts_incarcerated = matches!(classes[0], Class::Incarcerated);
+    let incarcerated_twice = classes
+        .windows(2)
+        .any(|pair| matches!(pair, [Class::Incarcerated, Class::Incarcerated]));
+    if packed != 0 || starts_incarcerated || incarcerated_twice {
+        return Err(CodeError::Malformed);
+    }
+
+    let mut demo = Demo::with_seed(seed);
+    demo.race = Some(race);
+    demo.class_zero = Some(classes[0]);
+    demo.history = classes[1..].to_vec();
+    demo.class_n = demo.history.last().copied();
+    Ok(demo)
+}
+
+/// A link to `base` that opens the journey `code`.
+#[must_use]
+pub fn link(base: &str, code: &str) -> String {
+    format!("{base}?{QUERY}={code}")
+}
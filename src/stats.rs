@@ -0,0 +1,123 @@
+//! What a group of journeys looks like together, next to what the weights say it should look like.
+
+use crate::{
+    demographic::{Class, Demo, Race},
+    model::Model,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Everyone of one [Race] in a session, counted up.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Group {
+    pub race: Race,
+    /// How many are in each quintile, followed by how many are incarcerated.
+    pub counts: [usize; 6],
+    /// Average number of quintiles moved up since the starting quintile, if anyone has one.
+    /// Anyone incarcerated counts from the quintile they were in before.
+    pub mobility: Option<f64>,
+    /// Share of the group expected in each quintile, then incarcerated, after the generations they've drawn.
+    pub expected: [f64; 6],
+}
+
+impl Group {
+    /// How many have a current class.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    #[must_use]
+    pub fn incarcerated(&self) -> usize {
+        self.counts[5]
+    }
+
+    /// Share of the group actually in each quintile, then incarcerated.
+    #[must_use]
+    pub fn observed(&self) -> [f64; 6] {
+        let total = self.total().max(1) as f64;
+        self.counts.map(|count| count as f64 / total)
+    }
+}
+
+/// A [Group] for each [Race], out of `demos`.
+///
+/// # Example
+/// ```
+/// use vocar::{stats, Demo, Race};
+///
+/// let demos: Vec<Demo> = (0..100)
+///     .map(|_| {
+///         let mut demo = Demo::new();
+///         while !demo.is_finished() {
+///             demo.draw_next();
+///         }
+///         demo
+///     })
+///     .collect();
+///
+/// let groups = stats::groups(&demos);
+/// assert_eq!(groups.iter().map(|g| g.total()).sum::<usize>(), 100);
+/// assert_eq!(groups[0].race, Race::Black);
+/// ```
+#[must_use]
+pub fn groups<'a>(demos: impl IntoIterator<Item = &'a Demo> + Clone) -> Vec<Group> {
+    [Race::Black, Race::White]
+        .into_iter()
+        .map(|race| {
+            let mut counts = [0; 6];
+            let mut expected = [0.0; 6];
+            let mut moves = Vec::new();
+
+            for demo in demos.clone().into_iter().filter(|d| d.race == Some(race)) {
+                match demo.class_n {
+                    Some(Class::Quintile(x)) => counts[(x - 1) as usize] += 1,
+                    Some(Class::Incarcerated) => counts[5] += 1,
+                    None => continue,
+                }
+                for (sum, share) in expected.iter_mut().zip(distribution(race, demo.history.len())) {
+                    *sum += share;
+                }
+                if let (Some(Class::Quintile(start)), Some(now)) = (demo.class_zero, latest_quintile(demo)) {
+                    moves.push(f64::from(now - start));
+                }
+            }
+
+            let total = counts.iter().sum::<usize>().max(1) as f64;
+            Group {
+                race,
+                counts,
+                mobility: (!moves.is_empty()).then(|| moves.iter().sum::<f64>() / moves.len() as f64),
+                expected: expected.map(|sum| sum / total),
+            }
+        })
+        .collect()
+}
+
+/// The quintile `demo` is in, or was in before being incarcerated.
+fn latest_quintile(demo: &Demo) -> Option<i8> {
+    demo.history
+        .iter()
+        .rev()
+        .chain(demo.class_zero.iter())
+        .find_map(|class| match class {
+            Class::Quintile(x) => Some(*x),
+            Class::Incarcerated => None,
+        })
+}
+
+/// The exact chance of being in each quintile, then incarcerated, after `generations` draws
+/// from a random starting quintile, following [Demo::weights] in each generation's era and [Demo::reentry_penalty].
+/// See [Model::distribution].
+///
+/// # Example
+/// ```
+/// use vocar::{stats, Race};
+///
+/// assert_eq!(stats::distribution(Race::White, 0), [0.2, 0.2, 0.2, 0.2, 0.2, 0.0]);
+/// assert!((stats::distribution(Race::Black, 5).iter().sum::<f64>() - 1.0).abs() < 1e-9);
+/// ```
+#[must_use]
+pub fn distribution(race: Race, generations: usize) -> [f64; 6] {
+    Model::baseline().distribution(race, generations)
+}
@@ -0,0 +1,231 @@
+//! The journey told as a story: a short beat for the starting [Class] and every generation after it.
+//!
+//! Beats are filled in from templates in `assets/stories/`, one file per [Lang], so they can be
+//! edited and translated without touching the code.
+
+use std::sync::OnceLock;
+
+use crate::{
+    demographic::{Class, Demo, Era, Race},
+    i18n::{Lang, Localize},
+};
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Streams of a journey's seed used for its story, well clear of the ones used for its draws.
+const STREAM: u64 = 1 << 32;
+
+/// What happened in a draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Event {
+    /// The starting [Class] was drawn.
+    Start,
+    /// A higher quintile than the generation before.
+    Rose,
+    /// The same quintile as the generation before.
+    Stayed,
+    /// A lower quintile than the generation before.
+    Fell,
+    /// Incarceration.
+    Arrested,
+    /// The generation after an incarceration, growing up with a parent's prison record.
+    Released,
+}
+
+impl Event {
+    /// The [Event] of drawing `to` after `from`, or as the starting [Class] without one.
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::{story::Event, Class};
+    ///
+    /// assert_eq!(Event::between(Some(Class::Quintile(2)), Class::Quintile(4)), Event::Rose);
+    /// assert_eq!(Event::between(Some(Class::Incarcerated), Class::Quintile(1)), Event::Released);
+    /// ```
+    #[must_use]
+    pub fn between(from: Option<Class>, to: Class) -> Event {
+        match (from, to) {
+            (None, _) => Event::Start,
+            (Some(_), Class::Incarcerated) => Event::Arrested,
+            (Some(Class::Incarcerated), _) => Event::Released,
+            (Some(Class::Quintile(before)), Class::Quintile(after)) => match after.cmp(&before) {
+                std::cmp::Ordering::Greater => Event::Rose,
+                std::cmp::Ordering::Equal => Event::Stayed,
+                std::cmp::Ordering::Less => Event::Fell,
+            },
+        }
+    }
+}
+
+/// One way of telling an [Event]. Conditions left out match anything.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Template {
+    pub event: Event,
+    #[serde(default)]
+    pub race: Option<Race>,
+    /// The [Class] of the generation before.
+    #[serde(default)]
+    pub from: Option<Class>,
+    /// The [Class] drawn.
+    #[serde(default)]
+    pub to: Option<Class>,
+    /// The generation, counting the first one after the starting [Class] as 1.
+    #[serde(default)]
+    pub generation: Option<usize>,
+    /// The beat, with `{name}` placeholders.
+    pub text: String,
+}
+
+impl Template {
+    /// How many conditions the template has, if `beat` meets them all.
+    fn specificity(&self, beat: &Beat) -> Option<usize> {
+        let conditions = [
+            self.race.map(|race| Some(race) == beat.race),
+            self.from.map(|from| Some(from) == beat.from),
+            self.to.map(|to| to == beat.to),
+            self.generation.map(|generation| generation == beat.generation),
+        ];
+        (self.event == beat.event && conditions.iter().all(|met| met.unwrap_or(true)))
+            .then(|| conditions.iter().flatten().count())
+    }
+}
+
+/// The templates of one [Lang].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Stories {
+    /// Who each generation is to the participant, from the first one after the starting [Class].
+    pub relatives: Vec<String>,
+    pub templates: Vec<Template>,
+}
+
+impl Stories {
+    /// The stories shipped in `assets/stories/` for `lang`.
+    #[must_use]
+    pub fn builtin(lang: Lang) -> &'static Stories {
+        static ENGLISH: OnceLock<Stories> = OnceLock::new();
+        static SPANISH: OnceLock<Stories> = OnceLock::new();
+
+        let (cell, source) = match lang {
+            Lang::English => (&ENGLISH, include_str!("../assets/stories/en.ron")),
+            Lang::Spanish => (&SPANISH, include_str!("../assets/stories/es.ron")),
+        };
+        cell.get_or_init(|| Stories::from_ron(source).expect("Failed To Parse Stories"))
+    }
+
+    /// Stories written like the ones in `assets/stories/`.
+    ///
+    /// # Errors
+    /// Fails if `source` isn't a set of stories.
+    pub fn from_ron(source: &str) -> Result<Stories, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    /// `beat` told in `lang`, by the most specific [Template] for it. Equally specific ones are
+    /// picked between with the journey's seed.
+    #[must_use]
+    pub fn tell(&self, lang: Lang, beat: &Beat) -> Option<String> {
+        let matching: Vec<(usize, &Template)> = self
+            .templates
+            .iter()
+            .filter_map(|template| template.specificity(beat).map(|specificity| (specificity, template)))
+            .collect();
+        let most = matching.iter().map(|(specificity, _)| *specificity).max()?;
+        let best: Vec<&Template> =
+            matching.into_iter().filter(|(specificity, _)| *specificity == most).map(|(_, template)| template).collect();
+
+        let mut rng = beat.rng();
+        let template = best.choose(&mut rng)?;
+        let relative = beat
+            .generation
+            .checked_sub(1)
+            .and_then(|index| self.relatives.get(index).or(self.relatives.last()))
+            .map_or("", String::as_str);
+        let from = beat.from.map(|from| from.localize(lang)).unwrap_or_default();
+        let race = beat.race.map(|race| race.localize(lang)).unwrap_or_default();
+
+        let text = [
+            ("relative", relative.to_string()),
+            ("from", from),
+            ("class", beat.to.localize(lang)),
+            ("race", race),
+            ("era", Era::of_generation(beat.generation.max(1)).localize(lang)),
+            ("generation", beat.generation.to_string()),
+            ("age", rng.gen_range(18..=35).to_string()),
+        ]
+        .iter()
+        .fold(template.text.clone(), |text, (name, value)| text.replace(&format!("{{{name}}}"), value));
+        Some(capitalize(&text))
+    }
+}
+
+/// A draw of a journey, as told in its story.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Beat {
+    pub event: Event,
+    pub race: Option<Race>,
+    pub from: Option<Class>,
+    pub to: Class,
+    /// 0 for the starting [Class], then 1 for the first generation after it and so on.
+    pub generation: usize,
+    /// The seed of the journey.
+    pub seed: u64,
+}
+
+impl Beat {
+    /// The draw that made generation `generation` of `demo`, if it has been made.
+    #[must_use]
+    pub fn of(demo: &Demo, generation: usize) -> Option<Beat> {
+        let to = match generation {
+            0 => demo.class_zero?,
+            _ => *demo.history.get(generation - 1)?,
+        };
+        let from = match generation {
+            0 => None,
+            1 => demo.class_zero,
+            _ => demo.history.get(generation - 2).copied(),
+        };
+        Some(Beat { event: Event::between(from, to), race: demo.race, from, to, generation, seed: demo.seed })
+    }
+
+    /// The generator for the details of the beat, the same every time it is told.
+    fn rng(&self) -> ChaCha8Rng {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        rng.set_stream(STREAM + self.generation as u64);
+        rng
+    }
+}
+
+/// `text` with its first letter in upper case.
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+}
+
+/// The beat of generation `generation` of `demo` in `lang`, falling back to the English stories.
+///
+/// # Example
+/// ```
+/// use vocar::{story, Class, Demo, Lang, Race};
+///
+/// let mut demo = Demo::with_seed(4);
+/// demo.race = Some(Race::White);
+/// demo.class_zero = Some(Class::Quintile(3));
+/// demo.history.push(Class::Incarcerated);
+///
+/// assert!(story::beat(Lang::English, &demo, 0).unwrap().starts_with("You "));
+/// assert!(story::beat(Lang::English, &demo, 1).unwrap().to_lowercase().contains("your child"));
+/// assert_eq!(story::beat(Lang::English, &demo, 2), None);
+/// ```
+#[must_use]
+pub fn beat(lang: Lang, demo: &Demo, generation: usize) -> Option<String> {
+    let beat = Beat::of(demo, generation)?;
+    Stories::builtin(lang).tell(lang, &beat).or_else(|| Stories::builtin(Lang::English).tell(Lang::English, &beat))
+}
+
+/// Every beat of `demo` drawn so far in `lang`, from the starting [Class] on.
+#[must_use]
+pub fn story(lang: Lang, demo: &Demo) -> Vec<String> {
+    (0..=demo.history.len()).map_while(|generation| beat(lang, demo, generation)).collect()
+}
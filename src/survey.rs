@@ -0,0 +1,324 @@
+//! Reflection surveys asked before a journey and after it, to measure what the activity changes.
+//!
+//! The questions are in `assets/survey.ron` and their wording in the message catalogs, so they are
+//! translated like the rest of the app. Answers stay on the device until exported as CSV.
+//!
+//! Researchers can ask their own questions without rebuilding the app: on desktop from a [FILE]
+//! next to the app, on the web from the URL in the [QUERY] parameter. Wording that isn't in the
+//! message catalogs is shown as written.
+
+use std::{
+    cell::OnceCell,
+    collections::BTreeMap,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::OnceLock,
+};
+
+use crate::{demographic::Demo, i18n::Lang};
+
+use serde::{Deserialize, Serialize};
+
+/// How an [Item] is answered.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Kind {
+    /// From 1, strongly disagree, to [Survey::LIKERT_POINTS], strongly agree.
+    Likert,
+    /// One of these options, each a message catalog key.
+    Choice(Vec<String>),
+    /// Anything the participant writes.
+    Text,
+}
+
+/// One question of a [Survey].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Item {
+    /// Names the answers in the export. Items asked before and after share it.
+    pub id: String,
+    /// Message catalog key of the question.
+    pub text: String,
+    pub kind: Kind,
+}
+
+/// The questions asked before a journey and after it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Survey {
+    pub before: Vec<Item>,
+    pub after: Vec<Item>,
+}
+
+impl Survey {
+    /// Points on every Likert scale.
+    pub const LIKERT_POINTS: u8 = 5;
+
+    /// The survey shipped in `assets/survey.ron`.
+    #[must_use]
+    pub fn builtin() -> &'static Survey {
+        static BUILTIN: OnceLock<Survey> = OnceLock::new();
+        BUILTIN.get_or_init(|| Survey::from_ron(include_str!("../assets/survey.ron")).expect("Failed To Parse Survey"))
+    }
+
+    /// A survey written like `assets/survey.ron`.
+    ///
+    /// # Errors
+    /// Fails if `source` isn't a survey.
+    pub fn from_ron(source: &str) -> Result<Survey, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    /// The survey in the file at `path`, written like `assets/survey.ron`.
+    ///
+    /// # Errors
+    /// Fails if the file can't be read or isn't a survey.
+    pub fn from_path(path: &Path) -> Result<Survey, LoadError> {
+        let source = std::fs::read_to_string(path).map_err(LoadError::Read)?;
+        Survey::from_ron(&source).map_err(LoadError::Parse)
+    }
+
+    /// The survey at `url`, written like `assets/survey.ron`.
+    ///
+    /// # Errors
+    /// Fails if the browser can't fetch it or it isn't a survey.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn fetch(url: &str) -> Result<Survey, LoadError> {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+
+        let read = |error: String| LoadError::Read(io::Error::new(io::ErrorKind::Other, error));
+        let js = |error: wasm_bindgen::JsValue| read(format!("{error:?}"));
+
+        let window = web_sys::window().ok_or_else(|| read("Err: No Window To Fetch From.".to_string()))?;
+        let response: web_sys::Response = JsFuture::from(window.fetch_with_str(url)).await.map_err(js)?.unchecked_into();
+        if !response.ok() {
+            return Err(read(format!("Err: The Survey Was Answered With {}.", response.status())));
+        }
+        let source = JsFuture::from(response.text().map_err(js)?).await.map_err(js)?;
+        Survey::from_ron(&source.as_string().unwrap_or_default()).map_err(LoadError::Parse)
+    }
+}
+
+/// Name of the file next to the app asked instead of the built-in survey.
+pub const FILE: &str = "survey.ron";
+
+/// The URL query parameter naming a survey to ask instead of the built-in one, as in
+/// `?survey={url}`.
+pub const QUERY: &str = "survey";
+
+/// Where a [FILE] next to the running app would be, if there is one.
+#[must_use]
+pub fn beside_app() -> Option<PathBuf> {
+    let path = std::env::current_exe().ok()?.parent()?.join(FILE);
+    path.is_file().then_some(path)
+}
+
+/// Why a survey couldn't be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file couldn't be read or fetched.
+    Read(io::Error),
+    /// It was read, but isn't written like `assets/survey.ron`.
+    Parse(ron::error::SpannedError),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Read(error) => write!(f, "Err: Couldn't Read The Survey: {error}"),
+            LoadError::Parse(error) => write!(f, "Err: That Isn't A Survey: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// What was answered to an [Item].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Answer {
+    /// The point picked, from 1 to [Survey::LIKERT_POINTS].
+    Likert(u8),
+    /// The index of the option picked.
+    Choice(usize),
+    Text(String),
+}
+
+impl Answer {
+    /// How the answer is written in the export. Options are written in English, whatever language
+    /// they were picked in, so answers can be compared.
+    fn export(&self, item: &Item) -> String {
+        match (self, &item.kind) {
+            (Answer::Likert(point), _) => point.to_string(),
+            (Answer::Choice(index), Kind::Choice(options)) => {
+                options.get(*index).map_or_else(String::new, |key| Lang::English.tr(key).to_string())
+            }
+            (Answer::Choice(index), _) => index.to_string(),
+            (Answer::Text(text), _) => text.clone(),
+        }
+    }
+}
+
+/// Answers by [Item::id].
+pub type Answers = BTreeMap<String, Answer>;
+
+/// Everything one participant answered, and the journey they answered it about.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Response {
+    /// When the session began, in seconds since the Unix epoch.
+    pub started: f64,
+    pub before: Answers,
+    pub after: Answers,
+    /// The finished journey, once the survey after it was handed in.
+    pub demo: Option<Demo>,
+}
+
+impl Response {
+    #[must_use]
+    pub fn new(started: f64) -> Self {
+        Response { started, before: Answers::new(), after: Answers::new(), demo: None }
+    }
+}
+
+/// Whether the surveys are asked, and every [Response] so far. Remembered between runs.
+///
+/// # Example
+/// ```
+/// use vocar::{survey::{Answer, Survey, Surveys}, Demo};
+///
+/// let mut surveys = Surveys::default();
+/// surveys.begin(1_700_000_000.0);
+/// surveys.current(0.0).before.insert("effort".to_string(), Answer::Likert(4));
+/// surveys.finish(0.0, &Demo::with_seed(1));
+///
+/// let csv = surveys.to_csv(Survey::builtin());
+/// assert!(csv.lines().nth(1).unwrap().starts_with("2023-11-14T22:13:20Z,"));
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Surveys {
+    /// The surveys are only asked if this is set.
+    pub enabled: bool,
+    /// Every [Response] handed in, oldest first.
+    pub responses: Vec<Response>,
+    /// The [Response] of the journey under way.
+    current: Option<Response>,
+    /// The survey given to [Surveys::ask], if any. Shared, so one fetched on the web can arrive
+    /// after the app has started.
+    #[serde(skip)]
+    asked: Rc<OnceCell<Survey>>,
+}
+
+impl Surveys {
+    /// Storage key the [Surveys] are saved under, apart from the rest of the app's state.
+    pub const KEY: &'static str = "vocar_surveys";
+
+    /// The questions asked: the ones given to [Surveys::ask], or [Survey::builtin] until then.
+    #[must_use]
+    pub fn survey(&self) -> &Survey {
+        self.asked.get().unwrap_or(Survey::builtin())
+    }
+
+    /// Ask `survey` instead of the built-in one. Only the first survey given is kept.
+    pub fn ask(&self, survey: Survey) {
+        if self.asked.set(survey).is_err() {
+            tracing::warn!("a survey was already being asked");
+        }
+    }
+
+    /// A handle to fill in with [OnceCell::set] later, for a survey that is still on its way.
+    #[must_use]
+    pub fn asked(&self) -> Rc<OnceCell<Survey>> {
+        Rc::clone(&self.asked)
+    }
+
+    /// Start a new participant's [Response] at `now`. One left unfinished by the participant
+    /// before is kept as it is.
+    pub fn begin(&mut self, now: f64) {
+        self.responses.extend(self.current.take());
+        self.current = Some(Response::new(now));
+    }
+
+    /// The [Response] of the journey under way, begun at `now` if there isn't one.
+    pub fn current(&mut self, now: f64) -> &mut Response {
+        self.current.get_or_insert_with(|| Response::new(now))
+    }
+
+    /// Hand in the current [Response], made about `demo`.
+    pub fn finish(&mut self, now: f64, demo: &Demo) {
+        let mut response = self.current.take().unwrap_or_else(|| Response::new(now));
+        response.demo = Some(demo.clone());
+        self.responses.push(response);
+    }
+
+    /// Forget every [Response].
+    pub fn clear(&mut self) {
+        self.responses.clear();
+        self.current = None;
+    }
+
+    /// Every [Response] as CSV, one row each, with a column per [Item] of `survey`.
+    #[must_use]
+    pub fn to_csv(&self, survey: &Survey) -> String {
+        let mut header = vec!["started".to_string(), "race".to_string(), "starting_class".to_string()];
+        header.extend((1..=Demo::GENERATIONS).map(|generation| format!("generation_{generation}")));
+        header.extend(survey.before.iter().map(|item| format!("before.{}", item.id)));
+        header.extend(survey.after.iter().map(|item| format!("after.{}", item.id)));
+
+        let mut csv = row(&header);
+        for response in self.responses.iter().chain(&self.current) {
+            let demo = response.demo.as_ref();
+            let mut cells = vec![
+                timestamp(response.started),
+                demo.and_then(|demo| demo.race).map(|race| race.to_string()).unwrap_or_default(),
+                demo.and_then(|demo| demo.class_zero).map(|class| class.to_string()).unwrap_or_default(),
+            ];
+            cells.extend((0..Demo::GENERATIONS).map(|generation| {
+                demo.and_then(|demo| demo.history.get(generation)).map(ToString::to_string).unwrap_or_default()
+            }));
+            for (items, answers) in [(&survey.before, &response.before), (&survey.after, &response.after)] {
+                cells.extend(
+                    items.iter().map(|item| answers.get(&item.id).map(|answer| answer.export(item)).unwrap_or_default()),
+                );
+            }
+            csv.push_str(&row(&cells));
+        }
+        csv
+    }
+}
+
+/// `cells` as a line of CSV, quoting the ones that need it. A cell a spreadsheet would take for a
+/// formula, like a written answer starting with `=`, gets a `'` in front so it is shown as text.
+fn row(cells: &[String]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| {
+            let cell =
+                if cell.starts_with(['=', '+', '-', '@', '\t', '\r']) { format!("'{cell}") } else { cell.clone() };
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell
+            }
+        })
+        .collect();
+    cells.join(",") + "\r\n"
+}
+
+/// `seconds` since the Unix epoch as an ISO 8601 date and time in UTC.
+fn timestamp(seconds: f64) -> String {
+    let seconds = seconds as i64;
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    // Days to a civil date, after Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z", time / 3600, time % 3600 / 60, time % 60)
+}
@@ -0,0 +1,227 @@
+//! What each class means in money, and the wealth a family builds up over a journey.
+//!
+//! Quintiles only say where someone ranks. The [Table] attaches income and wealth distributions to
+//! them for each [Race], and passes part of every generation's wealth down to the next.
+
+use std::sync::OnceLock;
+
+use crate::{
+    demographic::{Class, Demo, Era, Race},
+    model::Model,
+};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+/// Streams of a journey's seed used for its money, clear of the ones used for its draws and story.
+const STREAM: u64 = 2 << 32;
+
+/// A distribution of dollars, given by its 10th, 50th and 90th percentiles.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Spread {
+    pub low: f64,
+    pub median: f64,
+    pub high: f64,
+}
+
+impl Spread {
+    /// The amount at `rank`, from 0 for the poorest household to 1 for the richest. Between the
+    /// percentiles it is interpolated, and beyond them it stays at the nearest one.
+    ///
+    /// # Example
+    /// ```
+    /// use vocar::wealth::Spread;
+    ///
+    /// let spread = Spread { low: 10.0, median: 50.0, high: 250.0 };
+    /// assert_eq!(spread.at(0.5), 50.0);
+    /// assert!((spread.at(0.3) - 30.0).abs() < 1e-9);
+    /// assert_eq!(spread.at(1.0), 250.0);
+    /// ```
+    #[must_use]
+    pub fn at(&self, rank: f64) -> f64 {
+        let rank = rank.clamp(0.1, 0.9);
+        if rank <= 0.5 {
+            self.low + (self.median - self.low) * (rank - 0.1) / 0.4
+        } else {
+            self.median + (self.high - self.median) * (rank - 0.5) / 0.4
+        }
+    }
+}
+
+/// Yearly income and net worth of households, in dollars.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Money {
+    pub income: Spread,
+    pub wealth: Spread,
+}
+
+/// The money behind every [Class] for one [Race].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Classes {
+    /// Money of the 1st to 5th quintile.
+    pub quintiles: [Money; 5],
+    /// Yearly pay while in prison, and the net worth a sentence leaves behind: mostly the fines,
+    /// fees and debts owed on release.
+    pub incarcerated: Money,
+}
+
+/// The money behind every [Class] for each [Race], and how it is handed down.
+///
+/// # Example
+/// ```
+/// use vocar::{wealth::Table, Demo};
+///
+/// let mut demo = Demo::with_seed(7);
+/// while !demo.is_finished() {
+///     demo.draw_next();
+/// }
+///
+/// let ledger = Table::builtin().ledger(&demo);
+/// assert_eq!(ledger.len(), Demo::GENERATIONS + 1);
+/// assert!(ledger.iter().all(|generation| generation.wealth >= 0.0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Table {
+    pub black: Classes,
+    pub white: Classes,
+    /// Years of a sentence, during which the earnings of the quintile before are lost.
+    pub prison_years: f64,
+    /// Years each generation works and saves.
+    pub working_years: f64,
+    /// Share of income each generation saves.
+    pub savings_rate: f64,
+    /// Share of a family's wealth the next generation inherits.
+    pub inheritance: f64,
+}
+
+/// One generation of a family's money.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Generation {
+    pub class: Class,
+    /// Earned over the generation's working years.
+    pub earned: f64,
+    /// Earnings lost to a prison sentence.
+    pub lost: f64,
+    /// The family's wealth once the generation has inherited and saved. Debts aren't handed down,
+    /// so it is never below zero.
+    pub wealth: f64,
+}
+
+impl Table {
+    /// The table shipped in `assets/wealth.ron`.
+    #[must_use]
+    pub fn builtin() -> &'static Table {
+        static BUILTIN: OnceLock<Table> = OnceLock::new();
+        BUILTIN.get_or_init(|| Table::from_ron(include_str!("../assets/wealth.ron")).expect("Failed To Parse Wealth Table"))
+    }
+
+    /// A table written like `assets/wealth.ron`.
+    ///
+    /// # Errors
+    /// Fails if `source` isn't a table.
+    pub fn from_ron(source: &str) -> Result<Table, ron::error::SpannedError> {
+        ron::from_str(source)
+    }
+
+    #[must_use]
+    pub fn classes(&self, race: Race) -> &Classes {
+        match race {
+            Race::Black => &self.black,
+            Race::White => &self.white,
+        }
+    }
+
+    #[must_use]
+    pub fn money(&self, race: Race, class: Class) -> Money {
+        let classes = self.classes(race);
+        match class {
+            Class::Quintile(x) => classes.quintiles[(x.clamp(1, 5) - 1) as usize],
+            Class::Incarcerated => classes.incarcerated,
+        }
+    }
+
+    /// A generation of `race` in `class` after a family with `wealth`, whose last quintile was
+    /// `before`. `rank` places the household within its class, from 0 for the poorest to 1 for
+    /// the richest.
+    #[must_use]
+    pub fn next(&self, race: Race, class: Class, before: i8, wealth: f64, rank: f64) -> Generation {
+        let (earned, lost, owed) = match class {
+            Class::Quintile(_) => (self.working_years * self.money(race, class).income.at(rank), 0.0, 0.0),
+            Class::Incarcerated => {
+                let inside = self.money(race, class);
+                let outside = self.money(race, Class::Quintile(before)).income.at(rank);
+                let pay = inside.income.at(rank);
+                let years = self.prison_years.min(self.working_years);
+                (
+                    (self.working_years - years) * outside + years * pay,
+                    years * (outside - pay).max(0.0),
+                    inside.wealth.at(rank),
+                )
+            }
+        };
+
+        let wealth = wealth * self.inheritance + earned * self.savings_rate + owed;
+        Generation { class, earned, lost, wealth: wealth.max(0.0) }
+    }
+
+    /// The family's money for the starting [Class] of `demo`, then every generation drawn since.
+    /// Where each household ranks within its class comes from the journey's seed, so a journey
+    /// always has the same ledger.
+    #[must_use]
+    pub fn ledger(&self, demo: &Demo) -> Vec<Generation> {
+        let (Some(race), Some(start)) = (demo.race, demo.class_zero) else {
+            return Vec::new();
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(demo.seed);
+        rng.set_stream(STREAM);
+
+        let wealth = self.money(race, start).wealth.at(rng.gen());
+        let mut ledger = vec![Generation { class: start, earned: 0.0, lost: 0.0, wealth }];
+        let mut before = match start {
+            Class::Quintile(x) => x,
+            Class::Incarcerated => 1,
+        };
+        for &class in &demo.history {
+            let wealth = ledger.last().map_or(0.0, |generation| generation.wealth);
+            ledger.push(self.next(race, class, before, wealth, rng.gen()));
+            if let Class::Quintile(x) = class {
+                before = x;
+            }
+        }
+        ledger
+    }
+
+    /// The median family wealth after every generation of `runs` random journeys of `race`.
+    /// The same `seed` always gives the same result.
+    #[must_use]
+    #[instrument(level = "debug", skip(self))]
+    pub fn median(&self, race: Race, runs: usize, seed: u64) -> f64 {
+        let sampler = Model::baseline().rules(race).sampler();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        let mut wealths: Vec<f64> = (0..runs)
+            .map(|_| {
+                let mut before = rng.gen_range(1..=5);
+                let mut class = Class::Quintile(before);
+                let mut wealth = self.money(race, class).wealth.at(rng.gen());
+                for generation in 1..=Demo::GENERATIONS {
+                    class = sampler.next(Era::of_generation(generation), class, before, rng.gen());
+                    wealth = self.next(race, class, before, wealth, rng.gen()).wealth;
+                    if let Class::Quintile(x) = class {
+                        before = x;
+                    }
+                }
+                wealth
+            })
+            .collect();
+
+        wealths.sort_by(f64::total_cmp);
+        match wealths.len() {
+            0 => 0.0,
+            n if n % 2 == 0 => (wealths[n / 2 - 1] + wealths[n / 2]) / 2.0,
+            n => wealths[n / 2],
+        }
+    }
+}
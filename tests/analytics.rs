@@ -0,0 +1,160 @@
+//! The usage log keeps time per screen and each draw, and nothing personal.
+
+use vocar::{
+    analytics::{Analytics, Outcome, Session, Status},
+    Demo, Screen,
+};
+
+fn recording() -> Analytics {
+    let mut analytics = Analytics::default();
+    analytics.enabled = true;
+    analytics
+}
+
+/// Walk `demo` through a whole journey one second per screen, back to [Screen::Start].
+fn journey(analytics: &mut Analytics, demo: &mut Demo) {
+    let mut now = 0.0;
+    let mut show = |analytics: &mut Analytics, screen, demo: &Demo| {
+        analytics.observe(now, screen, demo);
+        now += 1.0;
+    };
+
+    show(analytics, Screen::Start, demo);
+    show(analytics, Screen::RaceDetermination, demo);
+    demo.draw_next();
+    show(analytics, Screen::RaceDisplay, demo);
+    while !demo.is_finished() {
+        show(analytics, Screen::ClassDetermination, demo);
+        demo.draw_next();
+        show(analytics, Screen::ClassDisplay, demo);
+        show(analytics, Screen::FactDisplay, demo);
+    }
+    show(analytics, Screen::MissedFactDisplay, demo);
+    show(analytics, Screen::End, demo);
+    demo.reset();
+    show(analytics, Screen::Start, demo);
+}
+
+#[test]
+fn a_finished_journey_is_one_finished_session() {
+    let mut analytics = recording();
+    let mut demo = Demo::with_seed(4);
+    journey(&mut analytics, &mut demo);
+
+    assert_eq!(analytics.sessions.len(), 1, "going back to the start doesn't begin another");
+    let session = &analytics.sessions[0];
+    assert_eq!(session.status, Status::Finished);
+    assert_eq!(session.race, demo.race);
+    assert_eq!(session.started, 1.0, "begun on leaving the start");
+    assert_eq!(session.visits.first().map(|visit| visit.screen), Some(Screen::RaceDetermination));
+    assert_eq!(session.visits.last().map(|visit| visit.screen), Some(Screen::End));
+    assert!(session.visits.iter().all(|visit| visit.left == Some(visit.entered + 1.0)));
+    assert_eq!(session.duration(), session.visits.len() as f64);
+
+    assert_eq!(session.steps.len(), 2 + Demo::GENERATIONS);
+    assert_eq!(session.steps[0].outcome, Outcome::Race(demo.race.unwrap()));
+    assert!(matches!(session.steps[1].outcome, Outcome::StartingClass(_)));
+    for (generation, step) in (1..).zip(&session.steps[2..]) {
+        assert!(matches!(step.outcome, Outcome::Generation { generation: g, .. } if g == generation));
+    }
+}
+
+#[test]
+fn sessions_without_a_draw_are_not_kept() {
+    let mut analytics = recording();
+    let mut demo = Demo::with_seed(4);
+
+    // Launched and left on the start screen, then a look at the scenarios.
+    analytics.observe(0.0, Screen::Start, &demo);
+    analytics.observe(5.0, Screen::Start, &demo);
+    analytics.observe(6.0, Screen::Scenarios, &demo);
+    analytics.observe(9.0, Screen::Start, &demo);
+    assert!(analytics.sessions.is_empty());
+
+    // Begun, but closed before the first draw.
+    analytics.observe(10.0, Screen::RaceDetermination, &demo);
+    assert_eq!(analytics.sessions.len(), 1, "in progress");
+    analytics.reopen();
+    assert!(analytics.sessions.is_empty());
+
+    analytics.observe(20.0, Screen::RaceDetermination, &demo);
+    demo.draw_next();
+    analytics.observe(21.0, Screen::RaceDisplay, &demo);
+    analytics.observe(22.0, Screen::Start, &demo);
+    assert_eq!(analytics.sessions.len(), 1);
+    assert_eq!(analytics.sessions[0].status, Status::Abandoned);
+}
+
+#[test]
+fn nothing_is_recorded_until_switched_on() {
+    let mut analytics = Analytics::default();
+    journey(&mut analytics, &mut Demo::with_seed(4));
+    assert!(analytics.sessions.is_empty());
+}
+
+#[test]
+fn switching_off_abandons_the_session() {
+    let mut analytics = recording();
+    let mut demo = Demo::with_seed(4);
+    analytics.observe(0.0, Screen::Start, &demo);
+    analytics.observe(3.0, Screen::RaceDetermination, &demo);
+    demo.draw_next();
+    analytics.observe(4.0, Screen::RaceDisplay, &demo);
+
+    analytics.enabled = false;
+    analytics.observe(7.0, Screen::RaceDisplay, &demo);
+
+    let session = &analytics.sessions[0];
+    assert_eq!(session.status, Status::Abandoned);
+    assert_eq!(session.visits[1].left, Some(7.0));
+    assert_eq!(session.duration(), 4.0);
+}
+
+#[test]
+fn undone_draws_are_not_recorded_twice() {
+    let mut analytics = recording();
+    let mut demo = Demo::with_seed(4);
+    analytics.observe(0.0, Screen::RaceDetermination, &demo);
+    demo.draw_next();
+    let before_class = demo.clone();
+    demo.draw_next();
+    analytics.observe(1.0, Screen::ClassDisplay, &demo);
+
+    // Going back restores the journey from before the draw, then it is drawn again.
+    demo = before_class;
+    analytics.observe(2.0, Screen::ClassDetermination, &demo);
+    demo.draw_next();
+    analytics.observe(3.0, Screen::ClassDisplay, &demo);
+
+    let steps: Vec<f64> = analytics.sessions[0].steps.iter().map(|step| step.at).collect();
+    assert_eq!(steps, [1.0, 1.0, 3.0]);
+}
+
+#[test]
+fn reopening_abandons_the_session_the_app_was_closed_in() {
+    let mut analytics = recording();
+    let mut demo = Demo::with_seed(4);
+    analytics.observe(0.0, Screen::Start, &demo);
+    analytics.observe(3.0, Screen::RaceDetermination, &demo);
+    demo.draw_next();
+    analytics.observe(4.0, Screen::RaceDisplay, &demo);
+
+    let mut reopened: Analytics = ron::from_str(&ron::to_string(&analytics).unwrap()).unwrap();
+    reopened.reopen();
+
+    let session = &reopened.sessions[0];
+    assert_eq!(session.status, Status::Abandoned);
+    assert_eq!(session.visits[1].left, None, "when the app was closed isn't known");
+}
+
+#[test]
+fn the_export_holds_only_the_sessions() {
+    let mut analytics = recording();
+    let mut demo = Demo::with_seed(4);
+    journey(&mut analytics, &mut demo);
+
+    let json = analytics.to_json().unwrap();
+    let sessions: Vec<Session> = serde_json::from_str(&json).unwrap();
+    assert_eq!(sessions, analytics.sessions);
+    assert!(!json.contains("seed") && !json.contains("enabled"));
+}
@@ -0,0 +1,23 @@
+//! A classroom draws everyone's journey in lock-step, however those journeys turn out.
+
+use vocar::{Class, Demo, Participant, Roster};
+
+#[test]
+fn a_roster_finishes_even_when_someone_is_released_in_the_first_generation() {
+    // Release looks back at the class before incarceration. For the first generation that is the
+    // starting class, which isn't in `history`, and looking for it there used to underflow.
+    let participants = (0..300)
+        .map(|seed| Participant { name: seed.to_string(), demo: Demo::with_seed(seed) })
+        .collect();
+    let mut roster = Roster { participants, draft: String::new() };
+
+    while !roster.is_finished() {
+        roster.draw_round();
+    }
+
+    assert!(
+        roster.participants.iter().any(|p| p.demo.history.first() == Some(&Class::Incarcerated)),
+        "some participant should have been incarcerated in the first generation"
+    );
+    assert!(roster.participants.iter().all(|p| p.demo.history.len() == Demo::GENERATIONS));
+}
@@ -0,0 +1,174 @@
+//! Every step of the screen flow, without drawing anything.
+
+use vocar::{
+    flow::{self, Action, FlowError, State},
+    Demo, Participant, Roster, Screen,
+};
+
+const SCREENS: [Screen; 16] = [
+    Screen::Start,
+    Screen::RaceDetermination,
+    Screen::RaceDisplay,
+    Screen::ClassDetermination,
+    Screen::ClassDisplay,
+    Screen::FactDisplay,
+    Screen::MissedFactDisplay,
+    Screen::End,
+    Screen::RosterSetup,
+    Screen::RosterDraw,
+    Screen::Scoreboard,
+    Screen::SharedJourney,
+    Screen::Scenarios,
+    Screen::Lineage,
+    Screen::PreSurvey,
+    Screen::PostSurvey,
+];
+
+fn drawn(seed: u64, draws: usize) -> Demo {
+    let mut demo = Demo::with_seed(seed);
+    for _ in 0..draws {
+        demo.draw_next();
+    }
+    demo
+}
+
+fn finished(seed: u64) -> Demo {
+    drawn(seed, Roster::STAGES)
+}
+
+fn roster(draws: usize) -> Roster {
+    let participants = ["Ada", "Bo"]
+        .into_iter()
+        .zip(1..)
+        .map(|(name, seed)| Participant { name: name.to_string(), demo: drawn(seed, draws) })
+        .collect();
+    Roster { participants, draft: String::new() }
+}
+
+/// A state with everything drawn, so every [Screen] can be shown.
+struct World {
+    demo: Demo,
+    roster: Roster,
+    shared: Option<Demo>,
+    surveys: bool,
+}
+
+impl World {
+    fn complete() -> Self {
+        World { demo: finished(1), roster: roster(Roster::STAGES), shared: Some(finished(2)), surveys: false }
+    }
+
+    fn state(&self) -> State<'_> {
+        State { demo: &self.demo, roster: &self.roster, shared: self.shared.as_ref(), surveys: self.surveys }
+    }
+}
+
+#[test]
+fn next_leads_from_every_screen_to_the_one_after_it() {
+    let world = World::complete();
+    let expected = [
+        (Screen::Start, Screen::RaceDetermination),
+        (Screen::RaceDetermination, Screen::RaceDisplay),
+        (Screen::RaceDisplay, Screen::ClassDetermination),
+        (Screen::ClassDetermination, Screen::ClassDisplay),
+        (Screen::ClassDisplay, Screen::FactDisplay),
+        (Screen::FactDisplay, Screen::MissedFactDisplay),
+        (Screen::MissedFactDisplay, Screen::End),
+        (Screen::End, Screen::Start),
+        (Screen::RosterSetup, Screen::RosterDraw),
+        (Screen::RosterDraw, Screen::Scoreboard),
+        (Screen::Scoreboard, Screen::Start),
+        (Screen::SharedJourney, Screen::Start),
+        (Screen::Scenarios, Screen::Start),
+        (Screen::Lineage, Screen::Start),
+        (Screen::PreSurvey, Screen::RaceDetermination),
+        (Screen::PostSurvey, Screen::Start),
+    ];
+    assert_eq!(expected.len(), SCREENS.len());
+
+    for (screen, next) in expected {
+        assert_eq!(flow::transition(screen, world.state(), Action::Next), Ok(next), "Next from {screen:?}");
+    }
+}
+
+#[test]
+fn next_depends_on_what_is_left_to_draw() {
+    let mut world = World::complete();
+    world.surveys = true;
+    assert_eq!(flow::transition(Screen::Start, world.state(), Action::Next), Ok(Screen::PreSurvey));
+    assert_eq!(flow::transition(Screen::End, world.state(), Action::Next), Ok(Screen::PostSurvey));
+
+    world.demo = drawn(1, 4);
+    assert_eq!(flow::transition(Screen::FactDisplay, world.state(), Action::Next), Ok(Screen::ClassDetermination));
+
+    world.roster = roster(3);
+    assert_eq!(flow::transition(Screen::RosterDraw, world.state(), Action::Next), Ok(Screen::RosterDraw));
+}
+
+#[test]
+fn the_other_actions_lead_to_the_same_screen_from_anywhere() {
+    let world = World::complete();
+    for screen in SCREENS {
+        for (action, next) in [
+            (Action::Restart, Screen::Start),
+            (Action::Classroom, Screen::RosterSetup),
+            (Action::Scenarios, Screen::Scenarios),
+            (Action::Lineage, Screen::Lineage),
+        ] {
+            assert_eq!(flow::transition(screen, world.state(), action), Ok(next), "{action:?} from {screen:?}");
+        }
+    }
+}
+
+#[test]
+fn each_missing_piece_has_its_own_error() {
+    let (demo, nobody) = (Demo::with_seed(1), Roster::default());
+    let empty = State { demo: &demo, roster: &nobody, shared: None, surveys: false };
+    let raced = drawn(1, 1);
+    let unfinished_demo = drawn(1, 4);
+    let unfinished_roster = roster(3);
+
+    let cases = [
+        (Screen::RaceDisplay, empty, FlowError::MissingRace(Screen::RaceDisplay)),
+        (Screen::ClassDisplay, State { demo: &raced, ..empty }, FlowError::MissingClass(Screen::ClassDisplay)),
+        (Screen::End, State { demo: &unfinished_demo, ..empty }, FlowError::Unfinished(Screen::End)),
+        (Screen::RosterDraw, empty, FlowError::EmptyRoster(Screen::RosterDraw)),
+        (
+            Screen::Scoreboard,
+            State { roster: &unfinished_roster, ..empty },
+            FlowError::RosterUnfinished(Screen::Scoreboard),
+        ),
+        (Screen::SharedJourney, empty, FlowError::NothingShared(Screen::SharedJourney)),
+    ];
+    for (screen, state, error) in cases {
+        assert_eq!(flow::check(screen, state), Err(error));
+        assert!(!error.to_string().is_empty());
+        assert_eq!(flow::guard(screen, state), Screen::Start, "{screen:?} falls back to the start");
+    }
+
+    assert_eq!(
+        flow::transition(Screen::RaceDetermination, empty, Action::Next),
+        Err(FlowError::MissingRace(Screen::RaceDisplay))
+    );
+    assert_eq!(
+        flow::transition(Screen::MissedFactDisplay, State { demo: &unfinished_demo, ..empty }, Action::Next),
+        Err(FlowError::Unfinished(Screen::End))
+    );
+}
+
+#[test]
+fn screens_needing_nothing_are_always_shown() {
+    let (demo, nobody) = (Demo::with_seed(1), Roster::default());
+    let empty = State { demo: &demo, roster: &nobody, shared: None, surveys: false };
+    let anytime =
+        [Screen::Start, Screen::RaceDetermination, Screen::RosterSetup, Screen::Scenarios, Screen::Lineage, Screen::PreSurvey];
+    for screen in anytime {
+        assert_eq!(flow::check(screen, empty), Ok(()));
+        assert_eq!(flow::guard(screen, empty), screen);
+    }
+
+    let world = World::complete();
+    for screen in SCREENS {
+        assert_eq!(flow::guard(screen, world.state()), screen, "{screen:?} with everything drawn");
+    }
+}
@@ -0,0 +1,89 @@
+//! Invariants of a [Demo] however its journey is driven.
+
+use proptest::prelude::*;
+use vocar::{Class, Demo};
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    DrawNext,
+    NextGen,
+    Reset,
+    RoundTrip,
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    prop_oneof![
+        3 => Just(Step::DrawNext),
+        3 => Just(Step::NextGen),
+        1 => Just(Step::Reset),
+        1 => Just(Step::RoundTrip),
+    ]
+}
+
+fn is_valid(class: Class) -> bool {
+    matches!(class, Class::Quintile(1..=5) | Class::Incarcerated)
+}
+
+fn check_invariants(demo: &Demo) -> Result<(), TestCaseError> {
+    let classes: Vec<Class> = demo.class_zero.iter().chain(&demo.history).copied().collect();
+
+    prop_assert!(classes.iter().all(|&class| is_valid(class)), "invalid class in {:?}", demo);
+    prop_assert!(demo.class_n.is_none_or(is_valid), "invalid current class in {:?}", demo);
+    prop_assert!(!matches!(demo.class_zero, Some(Class::Incarcerated)), "started incarcerated: {:?}", demo);
+    prop_assert!(
+        !classes.windows(2).any(|pair| pair == [Class::Incarcerated, Class::Incarcerated]),
+        "incarcerated twice in a row: {:?}",
+        demo
+    );
+    prop_assert_eq!(demo.class_n, demo.history.last().copied().or(demo.class_zero));
+    prop_assert!(!demo.to_string().is_empty());
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn journeys_keep_their_invariants(seed in any::<u64>(), steps in prop::collection::vec(step(), 0..40)) {
+        let mut demo = Demo::with_seed(seed);
+        check_invariants(&demo)?;
+
+        for step in steps {
+            let before = demo.clone();
+            match step {
+                Step::DrawNext => {
+                    demo.draw_next();
+                    prop_assert_eq!(demo.draws(), before.draws() + 1);
+                }
+                // Only a journey with a current class has a generation after it.
+                Step::NextGen if before.class_n.is_some() => {
+                    demo.next_gen();
+                    prop_assert_eq!(demo.history.len(), before.history.len() + 1);
+                }
+                Step::NextGen => {}
+                Step::Reset => {
+                    demo.reset();
+                    prop_assert_eq!(demo.race, before.race);
+                    prop_assert!(demo.history.is_empty() && demo.class_n.is_none() && demo.class_zero.is_none());
+                }
+                Step::RoundTrip => {
+                    let text = ron::to_string(&demo).unwrap();
+                    demo = ron::from_str(&text).unwrap();
+                    prop_assert_eq!(format!("{demo:?}"), format!("{before:?}"));
+                }
+            }
+            check_invariants(&demo)?;
+        }
+    }
+}
+
+/// A journey without a current class has no generation to draw after it.
+#[test]
+#[should_panic]
+fn next_gen_needs_a_current_class() {
+    let mut demo = Demo::with_seed(0);
+    demo.draw_next();
+    demo.draw_next();
+    demo.reset();
+
+    demo.next_gen();
+}
@@ -0,0 +1,97 @@
+//! Prediction mode keeps each guess next to the chances it was drawn with, and scores them.
+
+use vocar::{
+    quiz::{Guess, Prediction, Quiz},
+    Class, Demo, Race,
+};
+
+/// A [Demo] of `race` in quintile `x`, with its starting class drawn.
+fn at(race: Race, x: i8) -> Demo {
+    let mut demo = Demo::with_seed(5);
+    demo.race = Some(race);
+    demo.class_zero = Some(Class::Quintile(x));
+    demo.class_n = demo.class_zero;
+    demo
+}
+
+fn guessing() -> Quiz {
+    Quiz { enabled: true, ..Quiz::default() }
+}
+
+/// Guess `guess`, then draw the next generation of `demo`.
+fn draw(quiz: &mut Quiz, demo: &mut Demo, guess: Guess) {
+    quiz.guess = guess;
+    let before = demo.clone();
+    demo.draw_next();
+    quiz.record(&before, demo);
+}
+
+#[test]
+fn a_prediction_keeps_the_chances_of_its_draw() {
+    let mut quiz = guessing();
+    let mut demo = at(Race::Black, 1);
+    let chances = demo.chances().unwrap();
+    draw(&mut quiz, &mut demo, Guess { class: Some(Class::Quintile(1)), incarceration: Some(0.25) });
+
+    let prediction = quiz.prediction(1).unwrap();
+    assert_eq!(prediction.chances, chances);
+    assert_eq!(Some(prediction.drawn), demo.class_n);
+    assert_eq!(prediction.picked_likeliest(), Some(true), "staying in the 1st quintile is likeliest");
+    let error = prediction.incarceration_error().unwrap();
+    assert!((error - (0.25 - chances[5]).abs()).abs() < 1e-12);
+    assert_eq!(quiz.guess, Guess::default(), "the next draw starts without a guess");
+}
+
+#[test]
+fn nothing_is_kept_outside_prediction_mode_or_on_release() {
+    let mut quiz = Quiz::default();
+    let mut demo = at(Race::White, 3);
+    draw(&mut quiz, &mut demo, Guess { class: Some(Class::Quintile(3)), incarceration: None });
+    assert!(quiz.predictions.is_empty());
+
+    let mut quiz = guessing();
+    let mut demo = at(Race::White, 3);
+    demo.history.push(Class::Incarcerated);
+    demo.class_n = Some(Class::Incarcerated);
+    draw(&mut quiz, &mut demo, Guess { class: Some(Class::Quintile(1)), incarceration: None });
+    assert!(quiz.predictions.is_empty(), "release isn't left to chance");
+}
+
+#[test]
+fn drawing_again_replaces_the_prediction() {
+    let mut quiz = guessing();
+    let start = at(Race::Black, 4);
+
+    let mut demo = start.clone();
+    draw(&mut quiz, &mut demo, Guess { class: Some(Class::Quintile(1)), incarceration: None });
+    let mut demo = start;
+    demo.seed += 1;
+    draw(&mut quiz, &mut demo, Guess { class: Some(Class::Quintile(3)), incarceration: None });
+
+    assert_eq!(quiz.predictions.len(), 1);
+    assert_eq!(quiz.prediction(1).unwrap().guess.class, Some(Class::Quintile(3)));
+}
+
+#[test]
+fn the_score_adds_up_the_predictions() {
+    let prediction = |guess: Guess, drawn: Class| Prediction {
+        generation: 1,
+        guess,
+        chances: [0.1, 0.2, 0.4, 0.2, 0.05, 0.05],
+        drawn,
+    };
+    let quiz = Quiz {
+        enabled: true,
+        predictions: vec![
+            prediction(Guess { class: Some(Class::Quintile(3)), incarceration: Some(0.15) }, Class::Quintile(3)),
+            prediction(Guess { class: Some(Class::Quintile(1)), incarceration: Some(0.05) }, Class::Quintile(3)),
+            prediction(Guess { class: None, incarceration: None }, Class::Quintile(2)),
+        ],
+        ..Quiz::default()
+    };
+
+    let score = quiz.score();
+    assert_eq!((score.guessed, score.likeliest, score.came_true, score.estimated), (2, 1, 1, 2));
+    assert!((score.incarceration_error.unwrap() - 0.05).abs() < 1e-12);
+    assert_eq!(Quiz::default().score().incarceration_error, None);
+}
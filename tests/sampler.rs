@@ -0,0 +1,242 @@
+//! Checks that journeys are drawn the way the weight tables and the re-entry rules say.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use vocar::{
+    model::{compare, Intervention, Method, Model},
+    Class, Demo, Era, Eras, Race,
+};
+
+/// Draws per (race, era, quintile) row.
+const SAMPLES: u64 = 20_000;
+
+/// Critical value of the chi-square distribution with 5 degrees of freedom at p = 0.001.
+const CHI_SQUARE_5_DF: f64 = 20.515;
+
+/// Which quintile, or incarceration, `class` counts towards.
+fn bucket(class: Class) -> usize {
+    match class {
+        Class::Quintile(x) => (x - 1) as usize,
+        Class::Incarcerated => 5,
+    }
+}
+
+/// A [Demo] of `race` in `class` with `generations` already drawn, so its next draw is in the era
+/// of the generation after.
+fn demo_at(seed: u64, race: Race, class: Class, generations: usize) -> Demo {
+    let mut demo = Demo::with_seed(seed);
+    demo.race = Some(race);
+    demo.class_zero = Some(Class::Quintile(3));
+    demo.history = vec![class; generations];
+    demo.class_n = Some(class);
+    demo
+}
+
+fn chi_square(observed: &[u64; 6], weights: &[f64; 6]) -> f64 {
+    let total: f64 = weights.iter().sum();
+    observed
+        .iter()
+        .zip(weights)
+        .map(|(&count, weight)| {
+            let expected = SAMPLES as f64 * weight / total;
+            (count as f64 - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+#[test]
+fn draws_fit_the_weights_of_every_row() {
+    // Every row gets seeds of its own, so rows aren't all drawn from the same random numbers.
+    let mut seeds = (0..).step_by(SAMPLES as usize).map(|start| start..start + SAMPLES);
+    for race in [Race::Black, Race::White] {
+        for generation in 1..=Demo::GENERATIONS {
+            let era = Era::of_generation(generation);
+            for x in 1..=5 {
+                let mut observed = [0; 6];
+                for seed in seeds.next().unwrap() {
+                    let mut demo = demo_at(seed, race, Class::Quintile(x), generation - 1);
+                    demo.next_gen();
+                    observed[bucket(demo.class_n.unwrap())] += 1;
+                }
+
+                let weights = Demo::weights(race, era)[(x - 1) as usize];
+                let statistic = chi_square(&observed, &weights);
+                assert!(
+                    statistic < CHI_SQUARE_5_DF,
+                    "{race} in the {era} from quintile {x}: chi-square {statistic:.2} for {observed:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn bulk_draws_fit_the_weights_of_every_row() {
+    let model = Model::baseline();
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+    for race in [Race::Black, Race::White] {
+        let sampler = model.rules(race).sampler();
+        for era in Era::ALL {
+            for x in 1..=5 {
+                let mut observed = [0; 6];
+                for _ in 0..SAMPLES {
+                    observed[bucket(sampler.next(era, Class::Quintile(x), x, rng.gen()))] += 1;
+                }
+
+                let weights = Demo::weights(race, era)[(x - 1) as usize];
+                let statistic = chi_square(&observed, &weights);
+                assert!(
+                    statistic < CHI_SQUARE_5_DF,
+                    "{race} in the {era} from quintile {x}: chi-square {statistic:.2} for {observed:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn bulk_draws_agree_with_the_exact_distribution() {
+    let model = Model::baseline();
+    for race in [Race::Black, Race::White] {
+        let exact = model.distribution(race, Demo::GENERATIONS);
+        let simulated = model.simulate(race, Demo::GENERATIONS, 200_000, 0);
+        for (exact, simulated) in exact.iter().zip(simulated) {
+            assert!((exact - simulated).abs() < 0.01, "{race}: {exact:.4} exactly, {simulated:.4} simulated");
+        }
+    }
+}
+
+#[test]
+fn incarceration_only_comes_from_the_weights() {
+    // Incarceration is the last column, so a row without it must never draw it.
+    let mut weights = Demo::weights(Race::Black, Era::Tens)[0];
+    weights[5] = 0.0;
+    for i in 0..1000 {
+        assert_ne!(Class::pick(&weights, f64::from(i) / 1000.0), Class::Incarcerated);
+    }
+    assert_eq!(Class::pick(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0], 0.0), Class::Incarcerated);
+}
+
+#[test]
+fn release_drops_by_the_penalty_from_the_starting_class() {
+    for race in [Race::Black, Race::White] {
+        for x in 1..=5 {
+            let mut demo = demo_at(0, race, Class::Incarcerated, 1);
+            demo.class_zero = Some(Class::Quintile(x));
+            demo.next_gen();
+
+            let released = Class::Quintile((x - Demo::reentry_penalty(race)).max(1));
+            assert_eq!(demo.history, vec![Class::Incarcerated, released], "{race} from quintile {x}");
+            assert_eq!(demo.class_n, Some(released));
+        }
+    }
+}
+
+#[test]
+fn release_drops_by_the_penalty_from_the_generation_before() {
+    for race in [Race::Black, Race::White] {
+        for before in 1..=5 {
+            for generations in 2..Demo::GENERATIONS {
+                let mut demo = demo_at(0, race, Class::Quintile(5), generations - 1);
+                demo.history.pop();
+                demo.history.push(Class::Quintile(before));
+                demo.history.push(Class::Incarcerated);
+                demo.class_n = Some(Class::Incarcerated);
+                demo.next_gen();
+
+                let released = Class::Quintile((before - Demo::reentry_penalty(race)).clamp(1, 5));
+                assert_eq!(demo.history.len(), generations + 1);
+                assert_eq!(demo.history.last(), Some(&released), "{race} from quintile {before}");
+                assert_eq!(demo.class_n, Some(released));
+            }
+        }
+    }
+}
+
+#[test]
+fn a_release_is_recorded_as_the_generation_after_incarceration() {
+    for seed in 0..2000 {
+        let mut demo = Demo::with_seed(seed);
+        while !demo.is_finished() {
+            demo.draw_next();
+            if !demo.history.is_empty() {
+                assert_eq!(demo.class_n, demo.history.last().copied(), "seed {seed}");
+            }
+        }
+        assert_eq!(demo.history.len(), Demo::GENERATIONS, "seed {seed}");
+
+        let race = demo.race.unwrap();
+        let classes: Vec<Class> = demo.class_zero.iter().chain(&demo.history).copied().collect();
+        for window in classes.windows(3) {
+            if let [Class::Quintile(before), Class::Incarcerated, after] = *window {
+                let released = Class::Quintile((before - Demo::reentry_penalty(race)).clamp(1, 5));
+                assert_eq!(after, released, "seed {seed}: {classes:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn release_never_leaves_the_quintiles() {
+    for race in [Race::Black, Race::White] {
+        for before in 1..=5 {
+            let mut demo = demo_at(0, race, Class::Incarcerated, 1);
+            demo.class_zero = Some(Class::Quintile(before));
+            demo.next_gen();
+
+            assert!(matches!(demo.class_n, Some(Class::Quintile(1..=5))), "{race} from quintile {before}");
+        }
+    }
+}
+
+#[test]
+fn eras_only_change_the_chances_they_have_tables_for() {
+    let eras: Vec<Era> = (1..=Demo::GENERATIONS).map(Era::of_generation).collect();
+    for pair in eras.windows(2) {
+        assert_ne!(pair[0], pair[1], "generations share an era: {eras:?}");
+    }
+    assert!(!Eras::builtin().has_table(Era::Tens), "the 2010s are the baseline");
+    for race in [Race::Black, Race::White] {
+        for (i, era) in Era::ALL.into_iter().enumerate() {
+            assert_eq!(Eras::builtin().has_table(era), era != Era::Tens);
+            for later in &Era::ALL[i + 1..] {
+                assert_ne!(Demo::weights(race, era), Demo::weights(race, *later), "{race} in the {era} and the {later}");
+            }
+        }
+        let incarceration = |era| Demo::weights(race, era).iter().map(|row| row[5]).sum::<f64>();
+        assert!(incarceration(Era::Fifties) < incarceration(Era::Nineties), "mass incarceration came later");
+    }
+
+    let table = "((0, 0, 0, 0, 0, 1), (0, 1, 0, 0, 0, 0), (0, 0, 1, 0, 0, 0), (0, 0, 0, 1, 0, 0), (0, 0, 0, 0, 1, 0))";
+    let eras = Eras::from_ron(&format!(
+        "(generations: [Fifties, Nineties], tables: {{ Nineties: (black: {table}, white: {table}) }})"
+    ))
+    .unwrap();
+    assert_eq!(eras.of_generation(1), Era::Fifties);
+    assert_eq!(eras.of_generation(Demo::GENERATIONS), Era::Nineties);
+    assert_eq!(eras.weights(Race::White, Era::Fifties), Demo::weights(Race::White, Era::Tens));
+    assert_eq!(eras.weights(Race::White, Era::Nineties)[0], [0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn monte_carlo_comparisons_share_their_random_numbers() {
+    // Baby bonds move chances up within every row. Drawn from the same random numbers, no single
+    // journey can then land lower afterwards, however few are drawn.
+    let before = Model::baseline();
+    let after = before.clone().with(&[Intervention::BabyBonds]);
+    for race in [Race::Black, Race::White] {
+        for seed in 0..100 {
+            let before = before.simulate_paired(race, 1, 20, seed);
+            let after = after.simulate_paired(race, 1, 20, seed);
+            for x in 1..=5 {
+                let below = |shares: [f64; 6]| shares[..x].iter().sum::<f64>();
+                assert!(below(after) <= below(before) + 1e-9, "{race} from seed {seed}: {before:?} before, {after:?} after");
+            }
+        }
+    }
+
+    let compared = compare(&[Intervention::BabyBonds], Method::MonteCarlo { runs: 20, seed: 7 });
+    assert_eq!(compared[0].before, before.simulate_paired(compared[0].race, Demo::GENERATIONS, 20, 7));
+    assert_eq!(compared[0].after, after.simulate_paired(compared[0].race, Demo::GENERATIONS, 20, 7));
+}
@@ -0,0 +1,333 @@
+//! Walks the app through its screens on a headless [egui::Context], pressing buttons by name the
+//! way a screen reader would.
+
+use egui::accesskit::{Action, ActionRequest, DefaultActionVerb, Node, NodeId};
+use vocar::{Class, Demo, Lang, Localize, Screen, Vocar};
+
+struct Harness {
+    ctx: egui::Context,
+    vocar: Vocar,
+    /// Every node of the last frame's accessibility tree.
+    nodes: Vec<(NodeId, Node)>,
+    events: Vec<egui::Event>,
+}
+
+impl Harness {
+    fn new(vocar: Vocar) -> Self {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+
+        let mut harness = Harness { ctx, vocar, nodes: Vec::new(), events: Vec::new() };
+        harness.frame();
+        harness
+    }
+
+    fn frame(&mut self) {
+        let input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(1280.0, 800.0))),
+            events: std::mem::take(&mut self.events),
+            ..Default::default()
+        };
+        let output = self.ctx.run(input, |ctx| self.vocar.show(ctx, None));
+        self.nodes = output.platform_output.accesskit_update.map(|update| update.nodes).unwrap_or_default();
+    }
+
+    fn buttons(&self) -> impl Iterator<Item = &(NodeId, Node)> {
+        self.nodes.iter().filter(|(_, node)| node.default_action_verb() == Some(DefaultActionVerb::Click))
+    }
+
+    /// Press the button called `key` in English, then draw the frame that follows.
+    fn click(&mut self, key: &str) {
+        self.click_text(Lang::English.tr(key));
+    }
+
+    /// Press the button labelled `name`, then draw the frame that follows.
+    fn click_text(&mut self, name: &str) {
+        let matches: Vec<NodeId> =
+            self.buttons().filter(|(_, node)| node.name() == Some(name)).map(|(id, _)| *id).collect();
+        let names: Vec<&str> = self.buttons().filter_map(|(_, node)| node.name()).collect();
+        assert_eq!(matches.len(), 1, "Expected one {name:?} button on {:?}, found {names:?}", self.vocar.screen());
+
+        self.events.push(egui::Event::AccessKitActionRequest(ActionRequest {
+            action: Action::Default,
+            target: matches[0],
+            data: None,
+        }));
+        self.frame();
+        self.frame();
+    }
+
+    /// Whether some node of the last frame reads `key` in English.
+    fn has_text(&self, key: &str) -> bool {
+        self.reads(Lang::English.tr(key))
+    }
+
+    /// Whether some node of the last frame reads `text`.
+    fn reads(&self, text: &str) -> bool {
+        self.nodes.iter().any(|(_, node)| node.name() == Some(text) || node.value() == Some(text))
+    }
+
+    fn screen(&self) -> Screen {
+        self.vocar.screen()
+    }
+
+    fn demo(&self) -> &Demo {
+        self.vocar.demo()
+    }
+}
+
+#[test]
+fn a_journey_goes_from_start_to_end() {
+    let mut app = Harness::new(Vocar::default());
+    assert_eq!(app.screen(), Screen::Start);
+
+    app.click("start.begin");
+    assert_eq!(app.screen(), Screen::RaceDetermination);
+    assert!(app.demo().race.is_none());
+
+    app.click("race_determination.button");
+    assert_eq!(app.screen(), Screen::RaceDisplay);
+    assert!(app.demo().race.is_some());
+    let race = app.demo().race;
+
+    app.click("race_display.button");
+    assert_eq!(app.screen(), Screen::ClassDetermination);
+    assert!(app.demo().class_zero.is_none());
+
+    app.click("class_determination.start.button");
+    assert_eq!(app.screen(), Screen::ClassDisplay);
+    assert!(matches!(app.demo().class_zero, Some(Class::Quintile(1..=5))));
+    assert_eq!(app.demo().class_n, app.demo().class_zero);
+
+    app.click("class_display.button");
+    assert_eq!(app.screen(), Screen::FactDisplay);
+
+    for generation in 1..=Demo::GENERATIONS {
+        app.click("fact.button");
+        assert_eq!(app.screen(), Screen::ClassDetermination);
+
+        app.click("class_determination.next.button");
+        assert_eq!(app.screen(), Screen::ClassDisplay);
+        assert_eq!(app.demo().history.len(), generation);
+        assert_eq!(app.demo().class_n, app.demo().history.last().copied());
+
+        app.click("class_display.button");
+        assert_eq!(app.screen(), Screen::FactDisplay);
+    }
+    assert!(app.demo().is_finished());
+    assert_eq!(app.demo().race, race);
+    let last = app.demo().history.last().unwrap().localize(Lang::English);
+    assert!(app.reads(&format!("{}{last}", Lang::English.tr("card.final_class"))), "the card shows the final class");
+
+    app.click("fact.button");
+    assert_eq!(app.screen(), Screen::MissedFactDisplay);
+
+    app.click("missed_fact.button");
+    assert_eq!(app.screen(), Screen::End);
+    assert_eq!(app.demo().history.len(), Demo::GENERATIONS);
+
+    app.click("end.button");
+    assert_eq!(app.screen(), Screen::Start);
+    assert!(app.demo().class_zero.is_none() && app.demo().history.is_empty());
+
+    // The next journey draws everything again, the race included.
+    let seed = app.demo().seed;
+    app.click("start.begin");
+    app.click("race_determination.button");
+    assert_ne!(app.demo().seed, seed);
+    let mut fresh = Demo::with_seed(app.demo().seed);
+    fresh.draw_next();
+    assert_eq!(app.demo().race, fresh.race, "the race is drawn for the new journey");
+
+    app.click("race_display.button");
+    assert_eq!(app.screen(), Screen::ClassDetermination);
+    assert_eq!(app.demo().class_zero, None);
+    assert_eq!(app.demo().draws(), 1);
+}
+
+#[test]
+fn journeys_run_back_to_back_draw_everything_afresh() {
+    let mut app = Harness::new(Vocar::default());
+
+    for _ in 0..2 {
+        app.click("start.begin");
+        app.click("race_determination.button");
+        let mut fresh = Demo::with_seed(app.demo().seed);
+        fresh.draw_next();
+        assert_eq!(app.demo().race, fresh.race, "the race is drawn again with the journey's own seed");
+        assert!(app.demo().class_zero.is_none() && app.demo().history.is_empty());
+
+        app.click("race_display.button");
+        app.click("class_determination.start.button");
+        assert_eq!(app.demo().draws(), 2);
+        app.click("class_display.button");
+        for _ in 0..Demo::GENERATIONS {
+            app.click("fact.button");
+            app.click("class_determination.next.button");
+            app.click("class_display.button");
+        }
+        app.click("fact.button");
+        app.click("missed_fact.button");
+        assert_eq!(app.screen(), Screen::End);
+        assert_eq!(app.demo().history.len(), Demo::GENERATIONS);
+
+        app.click("end.button");
+        assert_eq!(app.screen(), Screen::Start);
+    }
+}
+
+#[test]
+fn a_journey_left_for_the_scenarios_is_not_carried_on() {
+    let mut app = Harness::new(Vocar::default());
+    app.click("start.begin");
+    app.click("race_determination.button");
+    app.click("race_display.button");
+    app.click("class_determination.start.button");
+    app.click("class_display.button");
+    for _ in 0..Demo::GENERATIONS {
+        app.click("fact.button");
+        app.click("class_determination.next.button");
+        app.click("class_display.button");
+    }
+    app.click("fact.button");
+    app.click("missed_fact.button");
+    app.click("scenarios.open");
+    assert_eq!(app.screen(), Screen::Scenarios);
+    app.click("scenarios.button");
+    assert_eq!(app.screen(), Screen::Start);
+
+    app.click("start.begin");
+    assert_eq!(app.demo().draws(), 0, "a new journey");
+    app.click("race_determination.button");
+    app.click("race_display.button");
+    app.click("class_determination.start.button");
+    assert!(app.demo().class_zero.is_some() && app.demo().history.is_empty());
+}
+
+#[test]
+fn surveys_are_asked_around_the_journey_once_switched_on() {
+    let mut app = Harness::new(Vocar::default());
+    app.click("survey.heading");
+    app.click("survey.enabled");
+
+    app.click("start.begin");
+    assert_eq!(app.screen(), Screen::PreSurvey);
+    app.click("survey.continue");
+    assert_eq!(app.screen(), Screen::RaceDetermination);
+
+    app.click("race_determination.button");
+    app.click("race_display.button");
+    app.click("class_determination.start.button");
+    app.click("class_display.button");
+    for _ in 0..Demo::GENERATIONS {
+        app.click("fact.button");
+        app.click("class_determination.next.button");
+        app.click("class_display.button");
+    }
+    app.click("fact.button");
+    app.click("missed_fact.button");
+    assert_eq!(app.screen(), Screen::End);
+
+    app.click("end.button");
+    assert_eq!(app.screen(), Screen::PostSurvey);
+    assert!(app.demo().is_finished(), "the journey is handed in with the survey");
+
+    app.click("survey.continue");
+    assert_eq!(app.screen(), Screen::Start);
+    assert!(app.demo().history.is_empty());
+}
+
+#[test]
+fn prediction_mode_reveals_the_chances_after_the_draw() {
+    let mut app = Harness::new(Vocar::default());
+    app.click("quiz.enabled");
+    app.click("start.begin");
+    app.click("race_determination.button");
+    app.click("race_display.button");
+    app.click("class_determination.start.button");
+    app.click("class_display.button");
+    app.click("fact.button");
+    assert!(!app.has_text("quiz.reveal.heading"));
+
+    app.click_text(&Class::Incarcerated.localize(Lang::English));
+    app.click("class_determination.next.button");
+    assert_eq!(app.screen(), Screen::ClassDisplay);
+    assert!(app.has_text("quiz.reveal.heading"));
+}
+
+#[test]
+fn back_returns_to_the_screen_before() {
+    let mut app = Harness::new(Vocar::default());
+
+    app.click("start.begin");
+    app.click("race_determination.button");
+    app.click("race_display.button");
+    assert_eq!(app.screen(), Screen::ClassDetermination);
+
+    app.click("nav.back");
+    assert_eq!(app.screen(), Screen::RaceDisplay);
+    assert!(app.demo().race.is_some());
+}
+
+#[test]
+fn only_a_facilitator_can_go_back_past_a_draw() {
+    let mut app = Harness::new(Vocar::default());
+    app.click("start.begin");
+    app.click("race_determination.button");
+    assert!(!app.has_text("nav.facilitator"), "participants can't switch facilitator mode on");
+    app.click("nav.back");
+    assert_eq!(app.screen(), Screen::RaceDisplay);
+
+    let mut vocar = Vocar::default();
+    vocar.set_facilitator(true);
+    let mut app = Harness::new(vocar);
+    app.click("start.begin");
+    app.click("race_determination.button");
+    app.click("nav.back");
+    assert_eq!(app.screen(), Screen::RaceDetermination);
+    assert!(app.demo().race.is_none());
+}
+
+#[test]
+fn race_display_without_a_race_falls_back_to_start() {
+    // `race_display_screen` unwraps the race, so it must never be drawn without one.
+    let vocar: Vocar = ron::from_str("(scr: RaceDisplay)").unwrap();
+    let app = Harness::new(vocar);
+
+    assert_eq!(app.screen(), Screen::Start);
+}
+
+#[test]
+fn class_display_without_a_class_falls_back_to_start() {
+    // `class_display_screen` unwraps the current class, so it must never be drawn without one.
+    let vocar: Vocar = ron::from_str("(scr: ClassDisplay, dem: (race: Some(Black), history: []))").unwrap();
+    let app = Harness::new(vocar);
+
+    assert_eq!(app.screen(), Screen::Start);
+}
+
+#[test]
+fn every_screen_can_be_restored_without_panicking() {
+    for screen in [
+        "Start",
+        "RaceDetermination",
+        "RaceDisplay",
+        "ClassDetermination",
+        "ClassDisplay",
+        "FactDisplay",
+        "MissedFactDisplay",
+        "End",
+        "RosterSetup",
+        "RosterDraw",
+        "Scoreboard",
+        "SharedJourney",
+        "Scenarios",
+        "Lineage",
+        "PreSurvey",
+        "PostSurvey",
+    ] {
+        let vocar: Vocar = ron::from_str(&format!("(scr: {screen})")).unwrap();
+        let mut app = Harness::new(vocar);
+        app.frame();
+    }
+}
@@ -0,0 +1,148 @@
+//! The session server, driven over real sockets with the in-process [Client].
+
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    thread,
+    time::Duration,
+};
+
+use vocar::{
+    server::{Client, Server},
+    Opened, Sessions,
+};
+
+/// A new server with a room opened on it.
+fn open() -> (Server, Client, Opened) {
+    let server = Server::spawn("127.0.0.1:0").unwrap();
+    let client = Client::new(server.addr());
+    let (status, body) = client.request("POST", "/rooms", "").unwrap();
+    assert_eq!(status, 201);
+    (server, client, serde_json::from_str(&body).unwrap())
+}
+
+#[test]
+fn a_new_room_is_empty_and_waiting() {
+    let (_server, client, opened) = open();
+    assert_eq!(opened.code.len(), 4);
+    assert!(!opened.key.is_empty());
+
+    let (status, body) = client.request("GET", &format!("/rooms/{}", opened.code), "").unwrap();
+    assert_eq!(status, 200);
+    let summary: vocar::Summary = serde_json::from_str(&body).unwrap();
+    assert_eq!((summary.participants, summary.stage, summary.finished), (0, 0, false));
+
+    assert_eq!(client.request("GET", "/rooms/NONE", "").unwrap().0, 404);
+    assert_eq!(client.request("GET", "/", "").unwrap().0, 200);
+}
+
+#[test]
+fn a_wrong_code_or_key_is_refused() {
+    let (_server, client, opened) = open();
+
+    assert_eq!(client.request("POST", "/rooms/NONE/participants", "Ada").unwrap().0, 404);
+    assert_eq!(client.request("POST", &format!("/rooms/{}/participants", opened.code), "  ").unwrap().0, 400);
+
+    let (status, _) = client.request("POST", &format!("/rooms/{}/advance?key=guess", opened.code), "").unwrap();
+    assert_eq!(status, 403);
+    let (status, _) = client.request("POST", &format!("/rooms/{}/advance", opened.code), "").unwrap();
+    assert_eq!(status, 403, "no key at all");
+}
+
+#[test]
+fn nobody_joins_once_the_first_draw_is_made() {
+    let (_server, client, opened) = open();
+    let join = format!("/rooms/{}/participants", opened.code);
+    let (status, body) = client.request("POST", &join, "Ada").unwrap();
+    assert_eq!(status, 201);
+    let id: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    let advance = format!("/rooms/{}/advance?key={}", opened.code, opened.key);
+    assert_eq!(client.request("POST", &advance, "").unwrap().0, 200);
+    assert_eq!(client.request("POST", &join, "Bo").unwrap().0, 409);
+
+    let participant = format!("/rooms/{}/participants/{}", opened.code, id["id"].as_str().unwrap());
+    let (status, body) = client.request("GET", &participant, "").unwrap();
+    assert_eq!(status, 200);
+    assert!(body.contains("Ada"));
+}
+
+#[test]
+fn participants_are_only_found_by_the_id_they_joined_with() {
+    let (_server, client, opened) = open();
+    let join = format!("/rooms/{}/participants", opened.code);
+    let (_, body) = client.request("POST", &join, "Ada").unwrap();
+    let id: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let id = id["id"].as_str().unwrap();
+    assert_eq!(id.len(), 16, "not a count that can be guessed: {id}");
+
+    for guess in ["0", "1", "0000000000000000"] {
+        assert_eq!(client.request("GET", &format!("{join}/{guess}"), "").unwrap().0, 404);
+    }
+    assert_eq!(client.request("GET", &format!("{join}/{id}"), "").unwrap().0, 200);
+}
+
+#[test]
+fn rooms_past_the_limit_are_refused() {
+    let (_server, client, _) = open();
+    for _ in 1..Sessions::MAX_ROOMS {
+        assert_eq!(client.request("POST", "/rooms", "").unwrap().0, 201);
+    }
+    assert_eq!(client.request("POST", "/rooms", "").unwrap().0, 503);
+}
+
+#[test]
+fn connections_past_the_limit_are_refused() {
+    let (server, client, _) = open();
+
+    // Connections that never send a request each hold on to a thread until they time out.
+    let idle: Vec<TcpStream> = (0..Server::MAX_CONNECTIONS).map(|_| TcpStream::connect(server.addr()).unwrap()).collect();
+    assert_eq!(client.request("GET", "/", "").unwrap().0, 503);
+
+    drop(idle);
+    for _ in 0..100 {
+        if client.request("GET", "/", "").unwrap().0 == 200 {
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("the server should take connections again once the idle ones are gone");
+}
+
+#[test]
+fn watchers_are_sent_every_change() {
+    let (server, client, opened) = open();
+    let watcher = Client::new(server.addr());
+    let code = opened.code.clone();
+    let watching = thread::spawn(move || watcher.events(&code, 2).unwrap());
+
+    // The watcher may connect after the first join, so keep changing the room until it has seen one.
+    let join = format!("/rooms/{}/participants", opened.code);
+    for i in 0.. {
+        assert_eq!(client.request("POST", &join, &format!("Participant {i}")).unwrap().0, 201);
+        thread::sleep(Duration::from_millis(50));
+        if watching.is_finished() || i == 100 {
+            break;
+        }
+    }
+
+    let summaries = watching.join().unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert!(summaries[1].version > summaries[0].version);
+    assert!(summaries[1].participants > summaries[0].participants);
+}
+
+#[test]
+fn an_oversized_body_is_refused_without_being_read() {
+    let (server, client, opened) = open();
+    let join = format!("/rooms/{}/participants", opened.code);
+    assert_eq!(client.request("POST", &join, &"A".repeat(64 * 1024)).unwrap().0, 413);
+
+    // A client claiming an enormous body gets an answer straight away, rather than the server
+    // setting aside room for it.
+    let mut stream = TcpStream::connect(server.addr()).unwrap();
+    write!(stream, "POST {join} HTTP/1.1\r\nContent-Length: 99999999999999\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 413 "), "{response}");
+}
@@ -0,0 +1,239 @@
+//! Renders every [Screen] on the CPU and compares it to the PNGs in `tests/snapshots`.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to write new PNGs after an intended change to a screen, or for a
+//! new one, then look them over before checking them in. Without it, a missing PNG fails. Screens
+//! that drift too far are saved under the target folder, next to an image of which pixels differ.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use egui::{epaint::Primitive, Color32, ImageData, Pos2, Rect, TextureId};
+use image::{Rgba, RgbaImage};
+use vocar::{share, Demo, Participant, Roster, Screen, Vocar};
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 600;
+
+/// How far apart a channel can be before a pixel counts as changed.
+const CHANNEL_TOLERANCE: u8 = 24;
+
+/// Share of pixels that may change, e.g. for the debug build warning or rounding in the rasterizer.
+const PIXEL_TOLERANCE: f64 = 0.005;
+
+/// Textures egui has uploaded so far, as premultiplied pixels.
+#[derive(Default)]
+struct Textures(HashMap<TextureId, (usize, usize, Vec<Color32>)>);
+
+impl Textures {
+    fn update(&mut self, delta: egui::TexturesDelta) {
+        for (id, delta) in delta.set {
+            let (width, height, pixels) = match delta.image {
+                ImageData::Color(image) => (image.size[0], image.size[1], image.pixels),
+                ImageData::Font(image) => (image.size[0], image.size[1], image.srgba_pixels(None).collect()),
+            };
+            match delta.pos {
+                None => {
+                    self.0.insert(id, (width, height, pixels));
+                }
+                Some([x, y]) => {
+                    let (full_width, _, full) = self.0.get_mut(&id).expect("Partial Update Of A Missing Texture");
+                    for row in 0..height {
+                        let start = (y + row) * *full_width + x;
+                        full[start..start + width].copy_from_slice(&pixels[row * width..(row + 1) * width]);
+                    }
+                }
+            }
+        }
+        for id in delta.free {
+            self.0.remove(&id);
+        }
+    }
+
+    fn sample(&self, id: TextureId, uv: Pos2) -> Color32 {
+        let Some((width, height, pixels)) = self.0.get(&id) else {
+            return Color32::WHITE;
+        };
+        let x = ((uv.x * *width as f32) as usize).min(width - 1);
+        let y = ((uv.y * *height as f32) as usize).min(height - 1);
+        pixels[y * width + x]
+    }
+}
+
+fn multiply(a: Color32, b: Color32) -> Color32 {
+    let channel = |a: u8, b: u8| ((u16::from(a) * u16::from(b) + 127) / 255) as u8;
+    Color32::from_rgba_premultiplied(channel(a.r(), b.r()), channel(a.g(), b.g()), channel(a.b(), b.b()), channel(a.a(), b.a()))
+}
+
+/// Premultiplied `source` over `target`.
+fn blend(target: &mut Rgba<u8>, source: Color32) {
+    let keep = 255 - u16::from(source.a());
+    for (channel, add) in target.0.iter_mut().zip(source.to_array()) {
+        *channel = (u16::from(add) + (u16::from(*channel) * keep + 127) / 255).min(255) as u8;
+    }
+}
+
+/// Fill every triangle of `mesh` inside `clip`, interpolating colours and texture coordinates.
+fn rasterize(canvas: &mut RgbaImage, textures: &Textures, clip: Rect, mesh: &egui::Mesh) {
+    let bounds = clip.intersect(Rect::from_min_size(Pos2::ZERO, egui::vec2(WIDTH as f32, HEIGHT as f32)));
+
+    for triangle in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+        let area = (b.pos - a.pos).x * (c.pos - a.pos).y - (b.pos - a.pos).y * (c.pos - a.pos).x;
+        if area.abs() < f32::EPSILON {
+            continue;
+        }
+
+        let min = a.pos.min(b.pos).min(c.pos).max(bounds.min);
+        let max = a.pos.max(b.pos).max(c.pos).min(bounds.max);
+        for y in min.y.floor() as u32..max.y.ceil() as u32 {
+            for x in min.x.floor() as u32..max.x.ceil() as u32 {
+                let p = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+                let edge = |from: Pos2, to: Pos2| ((to - from).x * (p - from).y - (to - from).y * (p - from).x) / area;
+                let (wa, wb, wc) = (edge(b.pos, c.pos), edge(c.pos, a.pos), edge(a.pos, b.pos));
+                if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                    continue;
+                }
+
+                let lerp = |a: u8, b: u8, c: u8| (wa * f32::from(a) + wb * f32::from(b) + wc * f32::from(c)).round() as u8;
+                let color = Color32::from_rgba_premultiplied(
+                    lerp(a.color.r(), b.color.r(), c.color.r()),
+                    lerp(a.color.g(), b.color.g(), c.color.g()),
+                    lerp(a.color.b(), b.color.b(), c.color.b()),
+                    lerp(a.color.a(), b.color.a(), c.color.a()),
+                );
+                let uv = egui::pos2(
+                    wa * a.uv.x + wb * b.uv.x + wc * c.uv.x,
+                    wa * a.uv.y + wb * b.uv.y + wc * c.uv.y,
+                );
+                blend(canvas.get_pixel_mut(x, y), multiply(color, textures.sample(mesh.texture_id, uv)));
+            }
+        }
+    }
+}
+
+/// `vocar` after a few frames, so anything that settles over its first frames has settled.
+fn render(mut vocar: Vocar) -> RgbaImage {
+    let ctx = egui::Context::default();
+    let mut textures = Textures::default();
+    let mut canvas = RgbaImage::new(WIDTH, HEIGHT);
+
+    for _ in 0..3 {
+        let input = egui::RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, egui::vec2(WIDTH as f32, HEIGHT as f32))),
+            ..Default::default()
+        };
+        let output = ctx.run(input, |ctx| vocar.show(ctx, None));
+        textures.update(output.textures_delta);
+
+        canvas = RgbaImage::from_pixel(WIDTH, HEIGHT, Rgba([0, 0, 0, 255]));
+        for primitive in ctx.tessellate(output.shapes) {
+            if let Primitive::Mesh(mesh) = &primitive.primitive {
+                rasterize(&mut canvas, &textures, primitive.clip_rect, mesh);
+            }
+        }
+    }
+
+    canvas
+}
+
+fn finished(seed: u64) -> Demo {
+    let mut demo = Demo::with_seed(seed);
+    while !demo.is_finished() {
+        demo.draw_next();
+    }
+    demo
+}
+
+fn drawn(seed: u64, draws: usize) -> Demo {
+    let mut demo = Demo::with_seed(seed);
+    for _ in 0..draws {
+        demo.draw_next();
+    }
+    demo
+}
+
+/// The app on `screen` with `demo` and `roster`, everything else fixed too.
+fn app(screen: Screen, demo: &Demo, roster: &Roster) -> Vocar {
+    let source = format!(
+        "(scr: {screen:?}, dem: {}, roster: {}, lineage: (children: 2, start: 3, seed: 1))",
+        ron::to_string(demo).unwrap(),
+        ron::to_string(roster).unwrap(),
+    );
+    ron::from_str(&source).unwrap()
+}
+
+fn roster(draws: usize) -> Roster {
+    let participants = ["Ada", "Bo", "Cy"]
+        .into_iter()
+        .zip(1..)
+        .map(|(name, seed)| Participant { name: name.to_string(), demo: drawn(seed, draws) })
+        .collect();
+    Roster { participants, draft: String::new() }
+}
+
+fn compare(name: &str, actual: &RgbaImage) {
+    let folder = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots");
+    let path = folder.join(format!("{name}.png"));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(&folder).unwrap();
+        actual.save(&path).unwrap();
+        return;
+    }
+    assert!(path.exists(), "{name} has no snapshot yet; run with UPDATE_SNAPSHOTS=1 and check {}", path.display());
+
+    let expected = image::open(&path).unwrap().to_rgba8();
+    assert_eq!(expected.dimensions(), actual.dimensions(), "{name} changed size");
+
+    let mut diff = RgbaImage::from_pixel(WIDTH, HEIGHT, Rgba([0, 0, 0, 255]));
+    let mut changed = 0;
+    for ((x, y, want), got) in expected.enumerate_pixels().zip(actual.pixels()) {
+        if want.0.iter().zip(got.0).any(|(a, b)| a.abs_diff(b) > CHANNEL_TOLERANCE) {
+            changed += 1;
+            diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        }
+    }
+
+    let share = f64::from(changed) / f64::from(WIDTH * HEIGHT);
+    if share > PIXEL_TOLERANCE {
+        let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("snapshots");
+        std::fs::create_dir_all(&out).unwrap();
+        actual.save(out.join(format!("{name}.png"))).unwrap();
+        diff.save(out.join(format!("{name}.diff.png"))).unwrap();
+        panic!("{name} differs in {:.2}% of pixels, see {}", share * 100.0, out.display());
+    }
+}
+
+#[test]
+fn every_screen_matches_its_snapshot() {
+    let empty = Roster::default();
+    let cases = [
+        (Screen::Start, drawn(1, 0), &empty),
+        (Screen::RaceDetermination, drawn(1, 0), &empty),
+        (Screen::RaceDisplay, drawn(1, 1), &empty),
+        (Screen::ClassDetermination, drawn(1, 1), &empty),
+        (Screen::ClassDisplay, drawn(1, 2), &empty),
+        (Screen::FactDisplay, drawn(1, 4), &empty),
+        (Screen::MissedFactDisplay, finished(1), &empty),
+        (Screen::End, finished(1), &empty),
+        (Screen::RosterSetup, drawn(1, 0), &empty),
+        (Screen::RosterDraw, drawn(1, 0), &roster(3)),
+        (Screen::Scoreboard, drawn(1, 0), &roster(Roster::STAGES)),
+        (Screen::Scenarios, drawn(1, 0), &empty),
+        (Screen::Lineage, drawn(1, 0), &empty),
+        (Screen::PreSurvey, drawn(1, 0), &empty),
+        (Screen::PostSurvey, finished(1), &empty),
+    ];
+
+    for (screen, demo, roster) in cases {
+        let vocar = app(screen, &demo, roster);
+        assert_eq!(vocar.screen(), screen);
+        compare(&format!("{screen:?}"), &render(vocar));
+    }
+
+    let source = format!("(scr: ClassDetermination, dem: {}, quiz: (enabled: true))", ron::to_string(&drawn(1, 2)).unwrap());
+    compare("Prediction", &render(ron::from_str(&source).unwrap()));
+
+    let mut shared = app(Screen::Start, &drawn(1, 0), &empty);
+    shared.open_code(&share::encode(&finished(2)).unwrap());
+    compare("SharedJourney", &render(shared));
+}
@@ -0,0 +1,108 @@
+//! Every draw of a journey is told as a beat of its story, from templates that can be edited and translated.
+
+use vocar::{
+    story::{self, Beat, Event, Stories},
+    Class, Demo, Lang, Race,
+};
+
+fn finished(seed: u64) -> Demo {
+    let mut demo = Demo::with_seed(seed);
+    while !demo.is_finished() {
+        demo.draw_next();
+    }
+    demo
+}
+
+/// A journey of `race` through `classes`, the first of them the starting one.
+fn through(race: Race, classes: &[Class]) -> Demo {
+    let mut demo = Demo::with_seed(8);
+    demo.race = Some(race);
+    demo.class_zero = classes.first().copied();
+    demo.history = classes[1..].to_vec();
+    demo.class_n = classes.last().copied();
+    demo
+}
+
+#[test]
+fn draws_are_told_as_events() {
+    assert_eq!(Event::between(None, Class::Incarcerated), Event::Start);
+    assert_eq!(Event::between(Some(Class::Quintile(3)), Class::Quintile(3)), Event::Stayed);
+    assert_eq!(Event::between(Some(Class::Quintile(3)), Class::Quintile(1)), Event::Fell);
+    assert_eq!(Event::between(Some(Class::Quintile(5)), Class::Incarcerated), Event::Arrested);
+
+    let demo = through(Race::White, &[Class::Quintile(2), Class::Incarcerated, Class::Quintile(1)]);
+    let released = Beat::of(&demo, 2).unwrap();
+    assert_eq!((released.event, released.from, released.to), (Event::Released, Some(Class::Incarcerated), Class::Quintile(1)));
+    assert_eq!(Beat::of(&demo, 3), None);
+}
+
+#[test]
+fn every_generation_has_a_beat_in_every_language() {
+    for seed in 0..200 {
+        let demo = finished(seed);
+        for lang in Lang::ALL {
+            let story = story::story(lang, &demo);
+            assert_eq!(story.len(), Demo::GENERATIONS + 1, "seed {seed} in {}", lang.name());
+            for beat in &story {
+                assert!(!beat.contains('{') && !beat.contains('}'), "{beat} has a placeholder left");
+                assert!(beat.starts_with(char::is_uppercase), "{beat} starts in lower case");
+            }
+        }
+    }
+}
+
+#[test]
+fn the_story_is_the_same_every_time_it_is_told() {
+    let demo = finished(12);
+    assert_eq!(story::story(Lang::English, &demo), story::story(Lang::English, &demo));
+    assert_eq!(story::story(Lang::English, &Demo::with_seed(12)), Vec::<String>::new());
+}
+
+#[test]
+fn the_most_specific_template_is_used() {
+    let relatives = &Stories::builtin(Lang::English).relatives;
+    let source = r#"(
+        relatives: RELATIVES,
+        templates: [
+            (event: Arrested, text: "{relative} was arrested at {age}."),
+            (event: Arrested, race: Some(Black), text: "{relative}, Black, was arrested in the {era}."),
+            (event: Arrested, race: Some(Black), generation: Some(5), text: "Not told."),
+            (event: Released, text: "{relative} grew up with a parent's record in the {class}."),
+        ],
+    )"#;
+    let stories = Stories::from_ron(&source.replace("RELATIVES", &ron::to_string(relatives).unwrap())).unwrap();
+    assert_eq!(stories.relatives.len(), Demo::GENERATIONS);
+
+    let demo = through(Race::Black, &[Class::Quintile(4), Class::Quintile(4), Class::Incarcerated, Class::Quintile(2)]);
+    let tell = |generation| stories.tell(Lang::English, &Beat::of(&demo, generation).unwrap());
+    assert_eq!(tell(2).unwrap(), "Your grandchild, Black, was arrested in the 1950s.");
+    assert_eq!(tell(3).unwrap(), "Your great-grandchild grew up with a parent's record in the 2nd Quintile.");
+    assert_eq!(tell(1), None, "no template for staying");
+
+    let beat = Beat { race: Some(Race::White), ..Beat::of(&demo, 2).unwrap() };
+    let age = stories.tell(Lang::English, &beat).unwrap();
+    assert!(age.starts_with("Your grandchild was arrested at "), "{age}");
+
+    let few = Stories::from_ron(&source.replace("RELATIVES", r#"["your child", "your grandchild"]"#)).unwrap();
+    let told = few.tell(Lang::English, &Beat::of(&demo, 3).unwrap()).unwrap();
+    assert!(told.starts_with("Your grandchild grew up"), "later generations keep the last relative: {told}");
+}
+
+#[test]
+fn arrests_and_releases_get_their_own_beats() {
+    let demo = through(Race::Black, &[Class::Quintile(1), Class::Incarcerated, Class::Quintile(1)]);
+    for lang in Lang::ALL {
+        let stories = Stories::builtin(lang);
+        for generation in 1..=2 {
+            let beat = Beat::of(&demo, generation).unwrap();
+            let told = story::beat(lang, &demo, generation).unwrap();
+            let template = stories
+                .templates
+                .iter()
+                .find(|template| template.event == beat.event && template.race == Some(Race::Black))
+                .expect("a template for Black participants");
+            let end = template.text.rsplit('}').next().unwrap();
+            assert!(!end.is_empty() && told.ends_with(end), "{told} isn't told from {}", template.text);
+        }
+    }
+}
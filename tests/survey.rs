@@ -0,0 +1,128 @@
+//! The reflection surveys read from their data file, translate, and export for the researchers.
+
+use vocar::{
+    survey::{Answer, Kind, LoadError, Survey, Surveys},
+    Demo, Lang,
+};
+
+fn finished(seed: u64) -> Demo {
+    let mut demo = Demo::with_seed(seed);
+    while !demo.is_finished() {
+        demo.draw_next();
+    }
+    demo
+}
+
+#[test]
+fn every_question_and_option_is_translated() {
+    let survey = Survey::builtin();
+    assert!(!survey.before.is_empty() && !survey.after.is_empty());
+
+    let mut keys: Vec<String> = (1..=Survey::LIKERT_POINTS).map(|point| format!("survey.likert.{point}")).collect();
+    for item in survey.before.iter().chain(&survey.after) {
+        keys.push(item.text.clone());
+        if let Kind::Choice(options) = &item.kind {
+            keys.extend(options.iter().cloned());
+        }
+    }
+    for lang in Lang::ALL {
+        for key in &keys {
+            assert_ne!(lang.tr(key), key, "{key} is missing in {}", lang.name());
+        }
+    }
+}
+
+#[test]
+fn a_survey_can_be_read_from_ron() {
+    let survey = Survey::from_ron(r#"(before: [(id: "a", text: "survey.effort", kind: Text)], after: [])"#).unwrap();
+    assert_eq!(survey.before[0].kind, Kind::Text);
+    assert!(Survey::from_ron("(before: [])").is_err());
+}
+
+#[test]
+fn a_survey_file_is_asked_instead_of_the_builtin_one() {
+    let folder = std::env::temp_dir().join(format!("vocar-survey-{}", std::process::id()));
+    std::fs::create_dir_all(&folder).unwrap();
+    let (good, bad) = (folder.join("good.ron"), folder.join("bad.ron"));
+    std::fs::write(&good, r#"(before: [(id: "own", text: "Our own question?", kind: Text)], after: [])"#).unwrap();
+    std::fs::write(&bad, "(before: [])").unwrap();
+
+    assert!(matches!(Survey::from_path(&folder.join("missing.ron")), Err(LoadError::Read(_))));
+    assert!(matches!(Survey::from_path(&bad), Err(LoadError::Parse(_))));
+    let loaded = Survey::from_path(&good).unwrap();
+    std::fs::remove_dir_all(&folder).unwrap();
+
+    let surveys = Surveys::default();
+    assert_eq!(surveys.survey(), Survey::builtin(), "the built-in survey until another is given");
+    surveys.ask(loaded.clone());
+    assert_eq!(surveys.survey(), &loaded);
+    assert_eq!(Lang::English.tr(&loaded.before[0].text), "Our own question?", "shown as written");
+    assert!(surveys.to_csv(surveys.survey()).lines().next().unwrap().ends_with(",before.own"));
+}
+
+#[test]
+fn each_response_is_one_row_with_its_journey() {
+    let survey = Survey::builtin();
+    let demo = finished(3);
+    let mut surveys = Surveys::default();
+
+    surveys.begin(0.0);
+    let before = &mut surveys.current(0.0).before;
+    before.insert("effort".to_string(), Answer::Likert(2));
+    before.insert("mobility".to_string(), Answer::Choice(3));
+    before.insert("expect".to_string(), Answer::Text("Not much, \"honestly\",\nmaybe".to_string()));
+    surveys.current(0.0).after.insert("effort".to_string(), Answer::Likert(5));
+    surveys.finish(60.0, &demo);
+
+    let csv = surveys.to_csv(survey);
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+    let header: Vec<&str> = lines[0].split(',').collect();
+    assert_eq!(header.len(), 3 + Demo::GENERATIONS + survey.before.len() + survey.after.len());
+    assert_eq!(header[0], "started");
+    assert!(header.contains(&"before.effort") && header.contains(&"after.effort"));
+
+    let row = lines[1];
+    assert!(row.starts_with("1970-01-01T00:00:00Z,"));
+    assert!(row.contains(&demo.race.unwrap().to_string()));
+    assert!(row.contains(&demo.history.last().unwrap().to_string()));
+    assert!(row.contains(",2,") && row.contains(",More than 30,"));
+    assert!(row.contains("\"Not much, \"\"honestly\"\",\nmaybe\""));
+    assert!(csv.ends_with("\r\n"));
+}
+
+#[test]
+fn written_answers_are_never_exported_as_formulas() {
+    let mut surveys = Surveys::default();
+    surveys.begin(0.0);
+    for (id, text) in [("expect", "=HYPERLINK(\"http://example.com\")"), ("effort", "+1"), ("mobility", "-2"), ("odds", "@SUM(A1)")] {
+        surveys.current(0.0).before.insert(id.to_string(), Answer::Text(text.to_string()));
+    }
+    surveys.finish(0.0, &finished(1));
+
+    let survey = Survey::from_ron(
+        r#"(before: [
+            (id: "expect", text: "", kind: Text),
+            (id: "effort", text: "", kind: Text),
+            (id: "mobility", text: "", kind: Text),
+            (id: "odds", text: "", kind: Text),
+        ], after: [])"#,
+    )
+    .unwrap();
+    let csv = surveys.to_csv(&survey);
+    let row = csv.lines().nth(1).unwrap();
+    assert!(row.ends_with(",\"'=HYPERLINK(\"\"http://example.com\"\")\",'+1,'-2,'@SUM(A1)"), "{row}");
+}
+
+#[test]
+fn an_unfinished_response_is_kept_when_the_next_one_begins() {
+    let mut surveys = Surveys::default();
+    surveys.begin(0.0);
+    surveys.current(0.0).before.insert("effort".to_string(), Answer::Likert(1));
+    surveys.begin(10.0);
+    surveys.finish(20.0, &finished(1));
+
+    assert_eq!(surveys.responses.len(), 2);
+    assert!(surveys.responses[0].demo.is_none());
+    assert_eq!(surveys.responses[0].before.get("effort"), Some(&Answer::Likert(1)));
+    assert!(surveys.responses[1].demo.is_some() && surveys.responses[1].before.is_empty());
+}
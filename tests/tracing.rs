@@ -0,0 +1,137 @@
+//! Draws and screen changes are reported through `tracing`, with what was drawn and why.
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use tracing::{field::Field, subscriber::with_default, Event, Subscriber};
+use tracing_subscriber::{field::Visit, layer::Context, prelude::*, Layer};
+use egui::accesskit::{Action, ActionRequest};
+use vocar::{Demo, Lang, Screen, Vocar};
+
+/// An event's fields as `(name, value)` pairs, in the order they were written.
+type Recorded = Vec<(String, String)>;
+
+/// Every event's fields.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Recorded>>>);
+
+impl Recorder {
+    fn events(&self, message: &str) -> Vec<Recorded> {
+        let events = self.0.lock().unwrap();
+        events.iter().filter(|fields| field(fields, "message") == Some(message)).cloned().collect()
+    }
+}
+
+struct Fields(Recorded);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push((field.name().to_string(), format!("{value:?}")));
+    }
+}
+
+impl<S: Subscriber> Layer<S> for Recorder {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let mut fields = Fields(Vec::new());
+        event.record(&mut fields);
+        self.0.lock().unwrap().push(fields.0);
+    }
+}
+
+fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+#[test]
+fn every_draw_is_reported_with_its_outcome() {
+    let recorder = Recorder::default();
+    let mut demo = Demo::with_seed(7);
+    with_default(tracing_subscriber::registry().with(recorder.clone()), || {
+        while !demo.is_finished() {
+            demo.draw_next();
+        }
+    });
+
+    let races = recorder.events("drew the race");
+    assert_eq!(races.len(), 1);
+    assert_eq!(field(&races[0], "race"), Some(demo.race.unwrap().to_string().as_str()));
+
+    let starts = recorder.events("drew the starting class");
+    assert_eq!(starts.len(), 1);
+    assert_eq!(field(&starts[0], "class"), Some(demo.class_zero.unwrap().to_string().as_str()));
+
+    let events = recorder.0.lock().unwrap();
+    let generations: Vec<&Recorded> = events
+        .iter()
+        .filter(|fields| {
+            matches!(field(fields, "message"), Some("drew a generation" | "released from incarceration"))
+        })
+        .collect();
+    assert_eq!(generations.len(), Demo::GENERATIONS);
+    for (fields, class) in generations.iter().zip(&demo.history) {
+        assert_eq!(field(fields, "to"), Some(class.to_string().as_str()));
+        if field(fields, "message") == Some("drew a generation") {
+            assert!(field(fields, "weights").is_some_and(|weights| weights.starts_with('[')));
+        }
+    }
+}
+
+#[test]
+fn replaying_a_journey_reports_no_draws() {
+    let mut demo = Demo::with_seed(7);
+    while !demo.is_finished() {
+        demo.draw_next();
+    }
+
+    let recorder = Recorder::default();
+    let replay = with_default(tracing_subscriber::registry().with(recorder.clone()), || {
+        demo.as_race(demo.race.unwrap().other())
+    });
+
+    assert_eq!(replay.history.len(), demo.history.len());
+    assert!(recorder.0.lock().unwrap().is_empty(), "{:?}", recorder.0.lock().unwrap());
+}
+
+#[test]
+fn changing_screens_is_reported() {
+    let recorder = Recorder::default();
+    let mut vocar = Vocar::default();
+    with_default(tracing_subscriber::registry().with(recorder.clone()), || {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let mut frame = |events| {
+            let input = egui::RawInput { events, ..Default::default() };
+            ctx.run(input, |ctx| vocar.show(ctx, None)).platform_output.accesskit_update.unwrap().nodes
+        };
+
+        let begin = Lang::English.tr("start.begin");
+        let nodes = frame(Vec::new());
+        let (target, _) = nodes.iter().find(|(_, node)| node.name() == Some(begin)).unwrap();
+        let press = ActionRequest { action: Action::Default, target: *target, data: None };
+        frame(vec![egui::Event::AccessKitActionRequest(press)]);
+    });
+
+    assert_eq!(vocar.screen(), Screen::RaceDetermination);
+    let changes = recorder.events("changed screen");
+    assert_eq!(changes.len(), 1);
+    assert_eq!(field(&changes[0], "from"), Some("Start"));
+    assert_eq!(field(&changes[0], "to"), Some("RaceDetermination"));
+    assert_eq!(field(&changes[0], "action"), Some("Next"));
+}
+
+#[test]
+fn falling_back_to_start_is_reported() {
+    let recorder = Recorder::default();
+    let mut vocar: Vocar = ron::from_str("(scr: End)").unwrap();
+    with_default(tracing_subscriber::registry().with(recorder.clone()), || {
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| vocar.show(ctx, None));
+    });
+
+    assert_eq!(vocar.screen(), Screen::Start);
+    let warnings = recorder.events("fell back to the start screen");
+    assert_eq!(warnings.len(), 1);
+    assert!(field(&warnings[0], "error").is_some_and(|error| error.contains("End")));
+}
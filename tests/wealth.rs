@@ -0,0 +1,50 @@
+//! The money behind a journey, from the wealth table.
+
+use vocar::{wealth::Table, Class, Demo, Race};
+
+#[test]
+fn a_sentence_costs_earnings_and_leaves_debts() {
+    let table = Table::builtin();
+    for race in [Race::Black, Race::White] {
+        let free = table.next(race, Class::Quintile(3), 3, 0.0, 0.5);
+        let jailed = table.next(race, Class::Incarcerated, 3, 0.0, 0.5);
+        assert_eq!(free.lost, 0.0);
+        assert!(jailed.lost > 0.0);
+        assert!(jailed.earned < free.earned);
+
+        // The debts owed on release come out of what the family had.
+        let inherited = 100_000.0;
+        let owed = table.money(race, Class::Incarcerated).wealth.median;
+        assert!(owed < 0.0);
+        let after = table.next(race, Class::Incarcerated, 3, inherited, 0.5).wealth;
+        let saved = jailed.earned * table.savings_rate;
+        assert!((after - (inherited * table.inheritance + saved + owed)).abs() < 1e-6);
+
+        let spender = Table { savings_rate: 0.0, ..table.clone() };
+        assert_eq!(spender.next(race, Class::Incarcerated, 3, 0.0, 0.5).wealth, 0.0, "debts aren't handed down");
+    }
+}
+
+#[test]
+fn the_same_quintile_holds_less_for_black_families() {
+    let table = Table::builtin();
+    for x in 1..=5 {
+        let (black, white) = (table.money(Race::Black, Class::Quintile(x)), table.money(Race::White, Class::Quintile(x)));
+        assert!(black.wealth.median < white.wealth.median, "quintile {x}");
+        assert!(black.income.median <= white.income.median, "quintile {x}");
+    }
+    assert!(table.median(Race::Black, 2_000, 0) < table.median(Race::White, 2_000, 0));
+}
+
+#[test]
+fn a_journey_always_has_the_same_ledger() {
+    let mut demo = Demo::with_seed(11);
+    assert!(Table::builtin().ledger(&demo).is_empty());
+    while !demo.is_finished() {
+        demo.draw_next();
+    }
+
+    let ledger = Table::builtin().ledger(&demo);
+    assert_eq!(ledger, Table::builtin().ledger(&demo));
+    assert_eq!(ledger.iter().skip(1).map(|generation| generation.class).collect::<Vec<_>>(), demo.history);
+}